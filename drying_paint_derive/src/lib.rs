@@ -0,0 +1,389 @@
+/* SPDX-License-Identifier: (Apache-2.0 OR MIT OR Zlib) */
+/* Copyright © 2026 Violet Leonard */
+
+//! Derive macros for `drying_paint`.  These are re-exported from that
+//! crate when its `derive` feature is enabled, and documented there.
+
+use proc_macro::TokenStream;
+use proc_macro2::{Span, TokenStream as TokenStream2};
+use quote::{format_ident, quote, quote_spanned};
+use syn::{
+    meta::ParseNestedMeta, parse_macro_input, parse_quote, spanned::Spanned,
+    Data, DataStruct, DeriveInput, Error, Fields, FnArg, GenericParam,
+    Generics, ImplItem, ItemImpl, Lifetime, Member, Result, Type,
+};
+
+const WATCHES_FN: &str = "__drying_paint_watches";
+
+/// The context lifetime and owner type a `Watcher` is implemented for.
+struct Target {
+    ctx: Lifetime,
+    owner: Type,
+}
+
+impl Default for Target {
+    fn default() -> Self {
+        Self {
+            ctx: Lifetime::new("'static", Span::call_site()),
+            owner: parse_quote!(::drying_paint::DefaultOwner),
+        }
+    }
+}
+
+impl Target {
+    fn parse_meta(&mut self, meta: ParseNestedMeta<'_>) -> Result<()> {
+        if meta.path.is_ident("ctx") {
+            self.ctx = meta.value()?.parse()?;
+            Ok(())
+        } else if meta.path.is_ident("owner") {
+            self.owner = meta.value()?.parse()?;
+            Ok(())
+        } else {
+            Err(meta.error("expected `ctx` or `owner`"))
+        }
+    }
+}
+
+#[proc_macro_derive(Watcher, attributes(watcher))]
+pub fn derive_watcher(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    expand_derive(input)
+        .unwrap_or_else(Error::into_compile_error)
+        .into()
+}
+
+fn expand_derive(input: DeriveInput) -> Result<TokenStream2> {
+    let mut target = Target::default();
+    for attr in &input.attrs {
+        if attr.path().is_ident("watcher") {
+            attr.parse_nested_meta(|meta| target.parse_meta(meta))?;
+        }
+    }
+    let fields = match &input.data {
+        Data::Struct(data) => &data.fields,
+        _ => {
+            return Err(Error::new(
+                Span::call_site(),
+                "Watcher can only be derived for structs",
+            ))
+        }
+    };
+    let mut children = Vec::new();
+    for (index, field) in fields.iter().enumerate() {
+        for attr in &field.attrs {
+            if attr.path().is_ident("watcher") {
+                attr.meta.require_path_only()?;
+                let member = match &field.ident {
+                    Some(ident) => Member::Named(ident.clone()),
+                    None => Member::Unnamed(index.into()),
+                };
+                children.push(quote_spanned! {field.span()=>
+                    ::drying_paint::WatcherInit::init_child(
+                        &mut init,
+                        |root: &mut Self| &mut root.#member,
+                    );
+                });
+            }
+        }
+    }
+    let name = &input.ident;
+    let (impl_generics, ty_generics, where_clause) =
+        input.generics.split_for_impl();
+    let Target { ctx, owner } = target;
+    let watches_fn = syn::Ident::new(WATCHES_FN, Span::call_site());
+    Ok(quote! {
+        impl #impl_generics ::drying_paint::Watcher<#ctx, #owner>
+            for #name #ty_generics #where_clause
+        {
+            fn init(
+                mut init: impl ::drying_paint::WatcherInit<#ctx, Self, #owner>,
+            ) {
+                #(#children)*
+                #[allow(unused_imports)]
+                use ::drying_paint::__private::NoWatches as _;
+                Self::#watches_fn(&mut init);
+            }
+        }
+    })
+}
+
+#[proc_macro_attribute]
+pub fn watches(args: TokenStream, input: TokenStream) -> TokenStream {
+    let mut target = Target::default();
+    let parser = syn::meta::parser(|meta| target.parse_meta(meta));
+    parse_macro_input!(args with parser);
+    let item = parse_macro_input!(input as ItemImpl);
+    expand_watches(target, item)
+        .unwrap_or_else(Error::into_compile_error)
+        .into()
+}
+
+fn expand_watches(target: Target, mut item: ItemImpl) -> Result<TokenStream2> {
+    if let Some((_, path, _)) = &item.trait_ {
+        return Err(Error::new_spanned(
+            path,
+            "#[watches] must be used on an inherent impl block",
+        ));
+    }
+    let mut watches = Vec::new();
+    for impl_item in &mut item.items {
+        let method = match impl_item {
+            ImplItem::Fn(method) => method,
+            _ => continue,
+        };
+        let count = method.attrs.len();
+        method.attrs.retain(|attr| !attr.path().is_ident("watch"));
+        if method.attrs.len() == count {
+            continue;
+        }
+        let sig = &method.sig;
+        let ident = &sig.ident;
+        match sig.inputs.first() {
+            Some(FnArg::Receiver(receiver))
+                if receiver.reference.is_some() => {}
+            _ => {
+                return Err(Error::new_spanned(
+                    sig,
+                    "#[watch] methods must take `&self` or `&mut self`",
+                ))
+            }
+        }
+        watches.push(match sig.inputs.len() {
+            1 => quote_spanned! {ident.span()=>
+                ::drying_paint::WatcherInit::watch(
+                    &mut *init,
+                    |root: &mut Self| {
+                        root.#ident();
+                    },
+                );
+            },
+            2 => quote_spanned! {ident.span()=>
+                ::drying_paint::WatcherInit::watch_explicit(
+                    &mut *init,
+                    |arg, root: &mut Self| {
+                        root.#ident(arg);
+                    },
+                );
+            },
+            _ => {
+                return Err(Error::new_spanned(
+                    &sig.inputs,
+                    "#[watch] methods may only take a `WatchArg` besides \
+                     `self`",
+                ))
+            }
+        });
+    }
+    let Target { ctx, owner } = target;
+    let watches_fn = syn::Ident::new(WATCHES_FN, Span::call_site());
+    item.items.push(parse_quote! {
+        #[doc(hidden)]
+        #[allow(unused_variables)]
+        pub(crate) fn #watches_fn<I>(init: &mut I)
+        where
+            I: ::drying_paint::WatcherInit<#ctx, Self, #owner>,
+        {
+            #(#watches)*
+        }
+    });
+    Ok(quote!(#item))
+}
+
+#[proc_macro_derive(WatchedFields)]
+pub fn derive_watched_fields(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    expand_watched_fields(input)
+        .unwrap_or_else(Error::into_compile_error)
+        .into()
+}
+
+/// The arguments to use a type with the given generic parameters, with
+/// `before` and `after` added.
+fn generic_args(
+    generics: &Generics,
+    before: TokenStream2,
+    after: TokenStream2,
+) -> TokenStream2 {
+    let params = generics.params.iter().map(|param| match param {
+        GenericParam::Lifetime(param) => {
+            let lifetime = &param.lifetime;
+            quote!(#lifetime)
+        }
+        GenericParam::Type(param) => {
+            let ident = &param.ident;
+            quote!(#ident)
+        }
+        GenericParam::Const(param) => {
+            let ident = &param.ident;
+            quote!(#ident)
+        }
+    });
+    quote!(<#before, #(#params,)* #after>)
+}
+
+fn expand_watched_fields(input: DeriveInput) -> Result<TokenStream2> {
+    let fields =
+        match &input.data {
+            Data::Struct(DataStruct {
+                fields: Fields::Named(fields),
+                ..
+            }) => &fields.named,
+            _ => return Err(Error::new(
+                Span::call_site(),
+                "WatchedFields can only be derived for structs with named \
+                 fields",
+            )),
+        };
+    let name = &input.ident;
+    let vis = &input.vis;
+    let companion = format_ident!("Watched{}", name);
+    let doc = format!(
+        "A [`{}`] whose fields are each watched separately, generated by \
+         `#[derive(WatchedFields)]`.",
+        name,
+    );
+    let count = fields.len();
+
+    let mut generics = input.generics.clone();
+    generics.params.insert(0, parse_quote!('ctx));
+    generics
+        .params
+        .push(parse_quote!(O: ?Sized = ::drying_paint::DefaultOwner));
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+    let (auto_impl_generics, orig_ty_generics, _) =
+        input.generics.split_for_impl();
+    let auto_args = generic_args(
+        &input.generics,
+        quote!('static),
+        quote!(::drying_paint::DefaultOwner),
+    );
+    let decl_params = &generics.params;
+    let mut default_generics = generics.clone();
+    default_generics
+        .make_where_clause()
+        .predicates
+        .push(parse_quote!(#name #orig_ty_generics: ::core::default::Default));
+    let default_where_clause = &default_generics.where_clause;
+
+    let mut methods = Vec::new();
+    let mut auto_methods = Vec::new();
+    for (index, field) in fields.iter().enumerate() {
+        let field_vis = &field.vis;
+        let ident = field.ident.as_ref().expect("fields are named");
+        let ty = &field.ty;
+        let get = format_ident!("get_{}", ident);
+        let get_unwatched = format_ident!("get_{}_unwatched", ident);
+        let get_auto = format_ident!("get_{}_auto", ident);
+        let set = format_ident!("set_{}", ident);
+        let set_external = format_ident!("set_{}_external", ident);
+        let set_auto = format_ident!("set_{}_auto", ident);
+        let get_doc = format!(
+            "Get a reference to `{}`, binding a watch closure.",
+            ident,
+        );
+        let get_unwatched_doc = format!(
+            "Get a reference to `{}`, without binding any watch closure.",
+            ident,
+        );
+        let set_doc = format!(
+            "Set `{}`, notifying watchers of it that it has changed.",
+            ident,
+        );
+        methods.push(quote! {
+            #[doc = #get_doc]
+            #field_vis fn #get(
+                &self,
+                ctx: ::drying_paint::WatchArg<'_, 'ctx, O>,
+            ) -> &#ty {
+                self.metas[#index].watched(ctx);
+                &self.value.#ident
+            }
+
+            #[doc = #get_unwatched_doc]
+            #field_vis fn #get_unwatched(&self) -> &#ty {
+                &self.value.#ident
+            }
+
+            #[doc = #set_doc]
+            #field_vis fn #set(
+                &mut self,
+                value: #ty,
+                ctx: ::drying_paint::WatchArg<'_, 'ctx, O>,
+            ) {
+                self.value.#ident = value;
+                self.metas[#index].trigger(ctx);
+            }
+
+            #field_vis fn #set_external(&mut self, value: #ty) {
+                self.value.#ident = value;
+                self.metas[#index].trigger_external();
+            }
+        });
+        auto_methods.push(quote! {
+            #field_vis fn #get_auto(&self) -> &#ty {
+                self.metas[#index].watched_auto();
+                &self.value.#ident
+            }
+
+            #field_vis fn #set_auto(&mut self, value: #ty) {
+                self.value.#ident = value;
+                self.metas[#index].trigger_auto();
+            }
+        });
+    }
+
+    Ok(quote! {
+        #[doc = #doc]
+        #vis struct #companion<#decl_params> #where_clause {
+            value: #name #orig_ty_generics,
+            metas: [::drying_paint::WatchedMeta<'ctx, O>; #count],
+        }
+
+        impl #impl_generics #companion #ty_generics #where_clause {
+            /// Create a new value, with each field watched separately.
+            #vis fn new(value: #name #orig_ty_generics) -> Self {
+                Self {
+                    value,
+                    metas: ::core::array::from_fn(|_| {
+                        ::drying_paint::WatchedMeta::new()
+                    }),
+                }
+            }
+
+            /// Consumes the value, returning the plain struct.
+            #vis fn into_inner(self) -> #name #orig_ty_generics {
+                self.value
+            }
+
+            /// Get a reference to the plain struct, without binding any
+            /// watch closure.
+            #vis fn get_unwatched(&self) -> &#name #orig_ty_generics {
+                &self.value
+            }
+
+            #(#methods)*
+        }
+
+        impl #impl_generics ::core::convert::From<#name #orig_ty_generics>
+            for #companion #ty_generics #where_clause
+        {
+            fn from(value: #name #orig_ty_generics) -> Self {
+                Self::new(value)
+            }
+        }
+
+        impl #impl_generics ::core::default::Default
+            for #companion #ty_generics #default_where_clause
+        {
+            fn default() -> Self {
+                Self::new(::core::default::Default::default())
+            }
+        }
+
+        ::drying_paint::__if_std! {
+            impl #auto_impl_generics #companion #auto_args #where_clause {
+                #(#auto_methods)*
+            }
+        }
+    })
+}
@@ -0,0 +1,360 @@
+/* SPDX-License-Identifier: (Apache-2.0 OR MIT OR Zlib) */
+/* Copyright © 2026 Violet Leonard */
+
+use core::{fmt, sync::atomic::Ordering};
+
+use crate::{SyncWatchedMeta, WatchArg};
+
+macro_rules! sync_watched_atomic_int {
+    (#[cfg($cfg:meta)] $(#[$attr:meta])* $name:ident, $($rest:tt)*) => {
+        #[cfg($cfg)]
+        sync_watched_atomic_int! { $(#[$attr])* $name, $($rest)* }
+    };
+    ($(#[$attr:meta])* $name:ident, $atomic:ident, $int:ty) => {
+        $(#[$attr])*
+        /// Any thread may change the value, and watch functions which read
+        /// it will be re-run on the next update of their context, only if
+        /// the value actually changed.  Share it with other threads by
+        /// placing it in an `Arc`.
+        #[derive(Default)]
+        pub struct $name {
+            meta: SyncWatchedMeta,
+            value: core::sync::atomic::$atomic,
+        }
+
+        impl fmt::Debug for $name {
+            fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                fmt::Debug::fmt(&self.get_unwatched(), f)
+            }
+        }
+
+        impl From<$int> for $name {
+            fn from(value: $int) -> Self {
+                Self::new(value)
+            }
+        }
+
+        impl $name {
+            /// Create a new watched atomic value.
+            pub fn new(value: $int) -> Self {
+                Self {
+                    meta: SyncWatchedMeta::new(),
+                    value: core::sync::atomic::$atomic::new(value),
+                }
+            }
+
+            /// Consumes the atomic, returning the contained value
+            pub fn into_inner(self) -> $int {
+                self.value.into_inner()
+            }
+
+            /// Load the value, binding a watch closure.
+            pub fn get<O: ?Sized>(&self, ctx: WatchArg<'_, '_, O>) -> $int {
+                self.meta.watched(ctx);
+                self.get_unwatched()
+            }
+
+            /// Load the value, without binding any watch closure.
+            pub fn get_unwatched(&self) -> $int {
+                self.value.load(Ordering::Acquire)
+            }
+
+            #[cfg(feature = "std")]
+            pub fn get_auto(&self) -> $int {
+                WatchArg::try_with_current(|arg| self.meta.watched(arg));
+                self.get_unwatched()
+            }
+
+            fn changed(&self, old: $int, new: $int) {
+                if old != new {
+                    self.meta.trigger();
+                }
+            }
+
+            /// Store a value.
+            pub fn store(&self, value: $int) {
+                self.swap(value);
+            }
+
+            /// Store a value, returning the previous value.
+            pub fn swap(&self, value: $int) -> $int {
+                let old = self.value.swap(value, Ordering::AcqRel);
+                self.changed(old, value);
+                old
+            }
+
+            /// Adds to the current value, returning the previous value.
+            /// This operation wraps around on overflow.
+            pub fn fetch_add(&self, value: $int) -> $int {
+                let old = self.value.fetch_add(value, Ordering::AcqRel);
+                self.changed(old, old.wrapping_add(value));
+                old
+            }
+
+            /// Subtracts from the current value, returning the previous
+            /// value.  This operation wraps around on overflow.
+            pub fn fetch_sub(&self, value: $int) -> $int {
+                let old = self.value.fetch_sub(value, Ordering::AcqRel);
+                self.changed(old, old.wrapping_sub(value));
+                old
+            }
+
+            /// Stores the maximum of the current value and the argument,
+            /// returning the previous value.
+            pub fn fetch_max(&self, value: $int) -> $int {
+                let old = self.value.fetch_max(value, Ordering::AcqRel);
+                self.changed(old, old.max(value));
+                old
+            }
+
+            /// Stores the minimum of the current value and the argument,
+            /// returning the previous value.
+            pub fn fetch_min(&self, value: $int) -> $int {
+                let old = self.value.fetch_min(value, Ordering::AcqRel);
+                self.changed(old, old.min(value));
+                old
+            }
+
+            /// Stores `new` if the current value is `current`.  The return
+            /// value is the previous value, which is wrapped in `Ok` if the
+            /// exchange succeeded.
+            pub fn compare_exchange(
+                &self,
+                current: $int,
+                new: $int,
+            ) -> Result<$int, $int> {
+                let result = self.value.compare_exchange(
+                    current,
+                    new,
+                    Ordering::AcqRel,
+                    Ordering::Acquire,
+                );
+                if result.is_ok() {
+                    self.changed(current, new);
+                }
+                result
+            }
+        }
+    };
+}
+
+sync_watched_atomic_int! {
+    /// A watched `u8` which may be modified from any thread.
+    SyncWatchedAtomicU8, AtomicU8, u8
+}
+sync_watched_atomic_int! {
+    /// A watched `u16` which may be modified from any thread.
+    SyncWatchedAtomicU16, AtomicU16, u16
+}
+sync_watched_atomic_int! {
+    /// A watched `u32` which may be modified from any thread.
+    SyncWatchedAtomicU32, AtomicU32, u32
+}
+sync_watched_atomic_int! {
+    #[cfg(target_has_atomic = "64")]
+    /// A watched `u64` which may be modified from any thread.
+    SyncWatchedAtomicU64, AtomicU64, u64
+}
+sync_watched_atomic_int! {
+    /// A watched `usize` which may be modified from any thread.
+    SyncWatchedAtomicUsize, AtomicUsize, usize
+}
+sync_watched_atomic_int! {
+    /// A watched `i8` which may be modified from any thread.
+    SyncWatchedAtomicI8, AtomicI8, i8
+}
+sync_watched_atomic_int! {
+    /// A watched `i16` which may be modified from any thread.
+    SyncWatchedAtomicI16, AtomicI16, i16
+}
+sync_watched_atomic_int! {
+    /// A watched `i32` which may be modified from any thread.
+    SyncWatchedAtomicI32, AtomicI32, i32
+}
+sync_watched_atomic_int! {
+    #[cfg(target_has_atomic = "64")]
+    /// A watched `i64` which may be modified from any thread.
+    SyncWatchedAtomicI64, AtomicI64, i64
+}
+sync_watched_atomic_int! {
+    /// A watched `isize` which may be modified from any thread.
+    SyncWatchedAtomicIsize, AtomicIsize, isize
+}
+
+/// A watched `bool` which may be modified from any thread.
+///
+/// Any thread may change the value, and watch functions which read it will
+/// be re-run on the next update of their context, only if the value
+/// actually changed.  Share it with other threads by placing it in an
+/// `Arc`.
+#[derive(Default)]
+pub struct SyncWatchedAtomicBool {
+    meta: SyncWatchedMeta,
+    value: core::sync::atomic::AtomicBool,
+}
+
+impl fmt::Debug for SyncWatchedAtomicBool {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Debug::fmt(&self.get_unwatched(), f)
+    }
+}
+
+impl From<bool> for SyncWatchedAtomicBool {
+    fn from(value: bool) -> Self {
+        Self::new(value)
+    }
+}
+
+impl SyncWatchedAtomicBool {
+    /// Create a new watched atomic value.
+    pub fn new(value: bool) -> Self {
+        Self {
+            meta: SyncWatchedMeta::new(),
+            value: core::sync::atomic::AtomicBool::new(value),
+        }
+    }
+
+    /// Consumes the atomic, returning the contained value
+    pub fn into_inner(self) -> bool {
+        self.value.into_inner()
+    }
+
+    /// Load the value, binding a watch closure.
+    pub fn get<O: ?Sized>(&self, ctx: WatchArg<'_, '_, O>) -> bool {
+        self.meta.watched(ctx);
+        self.get_unwatched()
+    }
+
+    /// Load the value, without binding any watch closure.
+    pub fn get_unwatched(&self) -> bool {
+        self.value.load(Ordering::Acquire)
+    }
+
+    #[cfg(feature = "std")]
+    pub fn get_auto(&self) -> bool {
+        WatchArg::try_with_current(|arg| self.meta.watched(arg));
+        self.get_unwatched()
+    }
+
+    fn changed(&self, old: bool, new: bool) {
+        if old != new {
+            self.meta.trigger();
+        }
+    }
+
+    /// Store a value.
+    pub fn store(&self, value: bool) {
+        self.swap(value);
+    }
+
+    /// Store a value, returning the previous value.
+    pub fn swap(&self, value: bool) -> bool {
+        let old = self.value.swap(value, Ordering::AcqRel);
+        self.changed(old, value);
+        old
+    }
+
+    /// Logical "and" with the current value, returning the previous value.
+    pub fn fetch_and(&self, value: bool) -> bool {
+        let old = self.value.fetch_and(value, Ordering::AcqRel);
+        self.changed(old, old & value);
+        old
+    }
+
+    /// Logical "or" with the current value, returning the previous value.
+    pub fn fetch_or(&self, value: bool) -> bool {
+        let old = self.value.fetch_or(value, Ordering::AcqRel);
+        self.changed(old, old | value);
+        old
+    }
+
+    /// Logical "xor" with the current value, returning the previous value.
+    pub fn fetch_xor(&self, value: bool) -> bool {
+        let old = self.value.fetch_xor(value, Ordering::AcqRel);
+        self.changed(old, old ^ value);
+        old
+    }
+
+    /// Stores `new` if the current value is `current`.  The return value is
+    /// the previous value, which is wrapped in `Ok` if the exchange
+    /// succeeded.
+    pub fn compare_exchange(
+        &self,
+        current: bool,
+        new: bool,
+    ) -> Result<bool, bool> {
+        let result = self.value.compare_exchange(
+            current,
+            new,
+            Ordering::AcqRel,
+            Ordering::Acquire,
+        );
+        if result.is_ok() {
+            self.changed(current, new);
+        }
+        result
+    }
+}
+
+#[cfg(all(test, feature = "std"))]
+mod tests {
+    use std::{cell::RefCell, rc::Rc, sync::Arc};
+
+    use crate::*;
+
+    #[test]
+    fn counted_from_threads() {
+        struct Content {
+            count: Arc<SyncWatchedAtomicU32>,
+            done: Arc<SyncWatchedAtomicBool>,
+            runs: u32,
+            seen: u32,
+        }
+
+        impl Watcher<'static> for Content {
+            fn init(mut init: impl WatcherInit<'static, Self>) {
+                init.watch(|root| {
+                    if root.done.get_auto() {
+                        root.seen = root.count.get_auto();
+                    }
+                    root.runs += 1;
+                });
+            }
+        }
+
+        let content = Rc::new(RefCell::new(Content {
+            count: Arc::default(),
+            done: Arc::default(),
+            runs: 0,
+            seen: 0,
+        }));
+        let mut ctx = WatchContext::new();
+        ctx.add_watcher(&Rc::downgrade(&content));
+        let count = Arc::clone(&content.borrow().count);
+        let done = Arc::clone(&content.borrow().done);
+        let threads: std::vec::Vec<_> = (0..4)
+            .map(|_| {
+                let count = Arc::clone(&count);
+                std::thread::spawn(move || {
+                    for _ in 0..10 {
+                        count.fetch_add(1);
+                    }
+                })
+            })
+            .collect();
+        for thread in threads {
+            thread.join().unwrap();
+        }
+        // count is not read until done is set
+        ctx.update();
+        assert_eq!(content.borrow().runs, 1);
+        done.store(true);
+        ctx.update();
+        assert_eq!(content.borrow().runs, 2);
+        assert_eq!(content.borrow().seen, 40);
+        done.store(true);
+        count.fetch_add(0);
+        ctx.update();
+        assert_eq!(content.borrow().runs, 2);
+    }
+}
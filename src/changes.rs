@@ -0,0 +1,108 @@
+/* SPDX-License-Identifier: (Apache-2.0 OR MIT OR Zlib) */
+/* Copyright © 2026 Violet Leonard */
+
+use {
+    alloc::{collections::VecDeque, rc::Rc},
+    core::{
+        cell::Cell,
+        fmt,
+        task::{Context, Poll, Waker},
+    },
+};
+
+use crate::{WatchArg, WatchContext, WatchName};
+
+struct ChangesState<T> {
+    buffer: Cell<VecDeque<T>>,
+    last: Cell<Option<T>>,
+    waker: Cell<Option<Waker>>,
+}
+
+/// The successive values of an expression, returned by
+/// [`WatchContext::changes`].
+///
+/// As an [`Iterator`], this yields the values which have been recorded so
+/// far, and then returns `None` until the context is updated again.
+pub struct Changes<T> {
+    state: Rc<ChangesState<T>>,
+}
+
+impl<T> fmt::Debug for Changes<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "(Changes)")
+    }
+}
+
+impl<T> Changes<T> {
+    fn pop_front(&self) -> Option<T> {
+        let mut buffer = self.state.buffer.take();
+        let item = buffer.pop_front();
+        self.state.buffer.set(buffer);
+        item
+    }
+
+    /// Poll for the next value, in the style of an asynchronous stream.
+    ///
+    /// Returns `Poll::Ready(None)` once no more values can be produced.
+    /// This happens when the watch which records them can never be re-run,
+    /// because every watched value it read the last time it ran has been
+    /// dropped, such as along with the owner or watcher holding them.
+    pub fn poll_next(&mut self, cx: &mut Context<'_>) -> Poll<Option<T>> {
+        if let Some(item) = self.pop_front() {
+            Poll::Ready(Some(item))
+        } else if Rc::strong_count(&self.state) == 1 {
+            Poll::Ready(None)
+        } else {
+            self.state.waker.set(Some(cx.waker().clone()));
+            Poll::Pending
+        }
+    }
+}
+
+impl<T> Iterator for Changes<T> {
+    type Item = T;
+
+    fn next(&mut self) -> Option<T> {
+        self.pop_front()
+    }
+}
+
+pub(crate) fn spawn_changes<'ctx, O, T, F>(
+    ctx: &mut WatchContext<'ctx, O>,
+    debug_name: WatchName,
+    func: F,
+) -> Changes<T>
+where
+    O: ?Sized,
+    T: 'ctx + Clone + PartialEq,
+    F: 'ctx + Fn(WatchArg<'_, 'ctx, O>, &mut O) -> T,
+{
+    let state = Rc::new(ChangesState {
+        buffer: Cell::default(),
+        last: Cell::new(None),
+        waker: Cell::new(None),
+    });
+    let watch_state = Rc::clone(&state);
+    ctx.add_watch_raw(debug_name, move |mut raw_arg| {
+        if Rc::strong_count(&watch_state) == 1 {
+            // nobody is listening any more
+            raw_arg.cancel();
+            return;
+        }
+        let (owner, arg) = raw_arg.as_owner_and_arg();
+        let value = func(arg, owner);
+        let last = watch_state.last.take();
+        if last.as_ref() == Some(&value) {
+            watch_state.last.set(last);
+        } else {
+            let mut buffer = watch_state.buffer.take();
+            buffer.push_back(value.clone());
+            watch_state.buffer.set(buffer);
+            watch_state.last.set(Some(value));
+            if let Some(waker) = watch_state.waker.take() {
+                waker.wake();
+            }
+        }
+    });
+    Changes { state }
+}
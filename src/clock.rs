@@ -0,0 +1,121 @@
+/* SPDX-License-Identifier: (Apache-2.0 OR MIT OR Zlib) */
+/* Copyright © 2026 Violet Leonard */
+
+use {
+    alloc::rc::Rc,
+    core::{cell::Cell, time::Duration},
+};
+
+/// A source of time for a [`WatchContext`](crate::WatchContext).
+///
+/// The context reads its clock at the start of each update, and uses it to
+/// decide which timers are due.  Time is measured from an arbitrary point,
+/// and the context ignores any time earlier than one it has already seen.
+pub trait Clock {
+    /// Get the current time.
+    fn now(&self) -> Duration;
+
+    /// Whether this clock keeps pace with the system's monotonic clock, so
+    /// that a thread may sleep until one of its deadlines.  Defaults to
+    /// false, in which case
+    /// [`wait_for_sync`](crate::WatchContext::wait_for_sync) does not wake
+    /// up for timers.
+    fn is_real_time(&self) -> bool {
+        false
+    }
+}
+
+/// A [`Clock`] which reads the system's monotonic clock, measuring from
+/// when it was created.  This is the default clock of a context.
+#[cfg(feature = "std")]
+#[derive(Clone, Copy, Debug)]
+pub struct StdClock {
+    epoch: std::time::Instant,
+}
+
+#[cfg(feature = "std")]
+impl Default for StdClock {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(feature = "std")]
+impl StdClock {
+    /// Create a new clock, starting from zero now.
+    pub fn new() -> Self {
+        Self {
+            epoch: std::time::Instant::now(),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl Clock for StdClock {
+    fn now(&self) -> Duration {
+        self.epoch.elapsed()
+    }
+
+    fn is_real_time(&self) -> bool {
+        true
+    }
+}
+
+/// A [`Clock`] which only moves when told to, for testing time based watch
+/// functions deterministically.
+///
+/// Clones of a `ManualClock` share the same time, so one can be given to a
+/// context while another is kept to move it forward.
+///
+/// # Examples
+/// ```rust
+///# use core::time::Duration;
+///# use drying_paint::*;
+/// struct Owner {
+///     timeout: WatchedTimer,
+///     timed_out: bool,
+/// }
+///
+/// let clock = ManualClock::new();
+/// let mut ctx = WatchContext::from_owner(Owner {
+///     timeout: WatchedTimer::new(Duration::from_secs(30)),
+///     timed_out: false,
+/// });
+/// ctx.set_clock(clock.clone());
+/// ctx.add_watch(|owner, arg| {
+///     owner.timed_out = owner.timeout.get(arg);
+/// });
+/// clock.advance(Duration::from_secs(29));
+/// ctx.update();
+/// assert!(!ctx.owner().timed_out);
+/// clock.advance(Duration::from_secs(1));
+/// ctx.update();
+/// assert!(ctx.owner().timed_out);
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct ManualClock {
+    now: Rc<Cell<Duration>>,
+}
+
+impl ManualClock {
+    /// Create a new clock, starting from zero.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Move the clock forward.
+    pub fn advance(&self, by: Duration) {
+        self.now.set(self.now.get() + by);
+    }
+
+    /// Set the time of the clock.
+    pub fn set(&self, now: Duration) {
+        self.now.set(now);
+    }
+}
+
+impl Clock for ManualClock {
+    fn now(&self) -> Duration {
+        self.now.get()
+    }
+}
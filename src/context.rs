@@ -7,22 +7,35 @@ use {
         rc::{Rc, Weak},
         vec::Vec,
     },
-    core::{any::Any, cell::Cell},
+    core::{
+        any::Any,
+        cell::Cell,
+        future::Future,
+        pin::Pin,
+        task::{self, Poll},
+        time::Duration,
+    },
 };
 
 use crate::{
-    sync::SyncContext,
+    changes::Changes,
+    sync::{ContextHandle, SyncContext},
+    timer::TimerQueue,
     trigger::{TriggeredWatch, Watch, WatchFrame},
-    RawWatchArg, WatchArg, WatchName, WatcherHolder,
+    Clock, RawWatchArg, WatchArg, WatchName, WatcherHolder,
 };
 
 #[cfg(all(feature = "std", doc))]
 use crate::Watched;
 
+#[cfg(feature = "std")]
+use std::time::Instant;
+
 pub(crate) struct FrameInfo<'ctx, O: ?Sized> {
     pub(crate) id: u8,
     pub(crate) post_set: Weak<WatchFrame<'ctx, O>>,
     pub(crate) sync_context: Weak<SyncContext<'ctx, O>>,
+    pub(crate) timers: Weak<TimerQueue>,
 }
 
 impl<'ctx, O: ?Sized> Clone for FrameInfo<'ctx, O> {
@@ -31,6 +44,7 @@ impl<'ctx, O: ?Sized> Clone for FrameInfo<'ctx, O> {
             id: self.id,
             post_set: Weak::clone(&self.post_set),
             sync_context: Weak::clone(&self.sync_context),
+            timers: Weak::clone(&self.timers),
         }
     }
 }
@@ -39,6 +53,7 @@ pub struct WatchContext<'ctx, O: ?Sized = DefaultOwner> {
     next_frame: Rc<WatchFrame<'ctx, O>>,
     other_frame: Vec<TriggeredWatch<'ctx, O>>,
     sync_context: Rc<SyncContext<'ctx, O>>,
+    timers: Rc<TimerQueue>,
     pub(crate) frame_info: FrameInfo<'ctx, O>,
     pub(crate) total_watch_count: usize,
     frame_limit: Option<usize>,
@@ -55,16 +70,19 @@ impl<'ctx, O> WatchContext<'ctx, O> {
         let next_frame = Rc::default();
         let other_frame = Vec::new();
         let sync_context = Rc::new(SyncContext::new());
+        let timers = Rc::new(TimerQueue::new());
         let frame_info = FrameInfo {
             id: 0,
             post_set: Rc::downgrade(&next_frame),
             sync_context: Rc::downgrade(&sync_context),
+            timers: Rc::downgrade(&timers),
         };
         let total_watch_count = 0;
         WatchContext {
             next_frame,
             other_frame,
             sync_context,
+            timers,
             frame_info,
             total_watch_count,
             frame_limit,
@@ -109,6 +127,98 @@ impl<'ctx, O: ?Sized> WatchContext<'ctx, O> {
         });
     }
 
+    /// Run a callback once the predicate returns true.
+    ///
+    /// The predicate is run like a watch function, being re-run whenever
+    /// the watched values it references change, until it first returns
+    /// true.  At that point the callback is run and the watch is cancelled.
+    #[cfg_attr(do_cycle_debug, track_caller)]
+    pub fn when<P, F>(&mut self, predicate: P, callback: F)
+    where
+        P: 'ctx + Fn(WatchArg<'_, 'ctx, O>, &mut O) -> bool,
+        F: 'ctx + FnOnce(&mut O),
+    {
+        let debug_name = WatchName::from_caller();
+        let callback = Cell::new(Some(callback));
+        self.add_watch_raw(debug_name, move |mut raw_arg| {
+            let done = {
+                let (owner, arg) = raw_arg.as_owner_and_arg();
+                predicate(arg, owner)
+            };
+            if done {
+                raw_arg.cancel();
+                if let Some(callback) = callback.take() {
+                    callback(raw_arg.context().owner());
+                }
+            }
+        });
+    }
+
+    /// Returns a future which resolves once the predicate returns true.
+    ///
+    /// The predicate is checked as described in [`when`](Self::when), so
+    /// the future will only make progress while this context is being
+    /// updated.  The watch stops once the future is dropped.
+    #[cfg_attr(do_cycle_debug, track_caller)]
+    pub fn until<P>(&mut self, predicate: P) -> Until
+    where
+        P: 'ctx + Fn(WatchArg<'_, 'ctx, O>, &mut O) -> bool,
+    {
+        let debug_name = WatchName::from_caller();
+        let state = Rc::new(UntilState::default());
+        let watch_state = Rc::clone(&state);
+        self.add_watch_raw(debug_name, move |mut raw_arg| {
+            if Rc::strong_count(&watch_state) == 1 {
+                // nobody is waiting any more
+                raw_arg.cancel();
+                return;
+            }
+            let (owner, arg) = raw_arg.as_owner_and_arg();
+            if predicate(arg, owner) {
+                raw_arg.cancel();
+                watch_state.done.set(true);
+                if let Some(waker) = watch_state.waker.take() {
+                    waker.wake();
+                }
+            }
+        });
+        Until { state }
+    }
+
+    /// Record every distinct value the expression takes.
+    ///
+    /// The expression is run like a watch function, being re-run whenever
+    /// the watched values it references change.  Each time it produces a
+    /// value different from the previous one (starting with the first), that
+    /// value is recorded in the returned [`Changes`], which may be drained
+    /// after each update.  The watch stops once the `Changes` is dropped.
+    ///
+    /// # Examples
+    /// ```rust
+    ///# use drying_paint::*;
+    /// #[derive(Default)]
+    /// struct Owner {
+    ///     value: WatchedCore<'static, i32, Owner>,
+    /// }
+    ///
+    /// let mut ctx = WatchContext::from_owner(Owner::default());
+    /// let mut changes = ctx.changes(|arg, owner| *owner.value.get(arg) / 2);
+    /// for i in 1..=5 {
+    ///     *ctx.owner().value.get_mut_external() = i;
+    ///     ctx.update();
+    /// }
+    /// assert_eq!(changes.collect::<Vec<_>>(), [0, 1, 2]);
+    /// ```
+    #[cfg_attr(do_cycle_debug, track_caller)]
+    pub fn changes<F, T>(&mut self, func: F) -> Changes<T>
+    where
+        F: 'ctx + Fn(WatchArg<'_, 'ctx, O>, &mut O) -> T,
+        T: 'ctx + Clone + PartialEq,
+    {
+        let debug_name = WatchName::from_caller();
+        crate::changes::spawn_changes(self, debug_name, func)
+    }
+
     pub fn add_watch_raw<F, N>(&mut self, debug_name: N, f: F)
     where
         F: 'ctx + Fn(RawWatchArg<'_, 'ctx, O>),
@@ -130,8 +240,73 @@ impl<'ctx, O: ?Sized> WatchContext<'ctx, O> {
         &mut self.owner
     }
 
+    /// Create a handle which other threads can use to post functions to be
+    /// run with the owner of this context.  See [`ContextHandle`].
+    pub fn remote(&self) -> ContextHandle<O> {
+        self.sync_context.create_handle()
+    }
+
+    /// Attempt to update this context, for use from asynchronous code.
+    ///
+    /// If there is any work for [`update`](Self::update) to do, this
+    /// updates the context and returns `Poll::Ready`.  Otherwise, the waker
+    /// from `cx` is registered to be woken when a
+    /// [`SyncTrigger`](crate::SyncTrigger) for this context fires, or a
+    /// function is posted through a [`ContextHandle`], and this returns
+    /// `Poll::Pending`.
+    ///
+    /// With the `std` feature and a clock which keeps pace with the
+    /// system's, such as the default [`StdClock`](crate::StdClock), the
+    /// waker is also woken when the next timer is due, from a helper
+    /// thread.  Otherwise, the caller is responsible for updating the
+    /// context once [`next_deadline`](Self::next_deadline) is reached.
+    pub fn poll_update(&mut self, cx: &mut task::Context<'_>) -> Poll<()> {
+        if self.poll_pending(cx) {
+            self.update();
+            Poll::Ready(())
+        } else {
+            Poll::Pending
+        }
+    }
+
+    /// Returns true if there are updates to process, or registers the
+    /// waker from `cx` to be woken once there are.
+    fn poll_pending(&self, cx: &mut task::Context<'_>) -> bool {
+        self.sync_context.register_waker(cx.waker());
+        if self.has_pending_updates() {
+            return true;
+        }
+        #[cfg(feature = "std")]
+        if let Some(timeout) = self.timers.sleep_timeout() {
+            self.sync_context.wake_after(timeout);
+        }
+        false
+    }
+
+    /// Returns a future which resolves once this context has updates to
+    /// process.  See [`poll_update`](Self::poll_update) for what wakes it,
+    /// including when timers are due.
+    ///
+    /// # Examples
+    /// ```rust,no_run
+    ///# use drying_paint::WatchContext;
+    /// async fn run(mut ctx: WatchContext<'_>) {
+    ///     loop {
+    ///         ctx.updates_pending().await;
+    ///         ctx.update();
+    ///     }
+    /// }
+    /// ```
+    pub fn updates_pending(&self) -> UpdatesPending<'_, 'ctx, O> {
+        UpdatesPending { ctx: self }
+    }
+
     pub fn update(&mut self) {
+        self.timers.tick();
         self.sync_context.check_for_updates();
+        for func in self.sync_context.take_posted() {
+            func(&mut self.owner);
+        }
         let mut current_frame = core::mem::take(&mut self.other_frame);
         self.next_frame.swap(Cell::from_mut(&mut current_frame));
         if let Some(mut frame_limit) = self.frame_limit {
@@ -190,7 +365,11 @@ impl<'ctx, O: ?Sized> WatchContext<'ctx, O> {
     /// value updates the other.  For this purpose, you may be interested in
     /// a function which only triggers a watch if the value has actually
     /// changed, such as [`Watched::set_if_neq`].  The following example
-    /// panics, but it wouldn't if used [`Watched::set_if_neq`].
+    /// panics, but it wouldn't if used [`Watched::set_if_neq`].  If the two
+    /// values are converted to and from each other, the conversions may not
+    /// settle on values that are equal; use
+    /// [`WatcherInit::bind_two_way`](crate::WatcherInit::bind_two_way)
+    /// instead.
     ///
     /// # Examples
     /// ```rust,should_panic
@@ -227,6 +406,62 @@ impl<'ctx, O: ?Sized> WatchContext<'ctx, O> {
         self.frame_limit = value;
     }
 
+    /// Get the current time of this context's clock, as of the last
+    /// update.  With the default [`StdClock`](crate::StdClock), this is the
+    /// time since the context was created.
+    pub fn now(&self) -> Duration {
+        self.timers.now()
+    }
+
+    /// Replace the clock this context reads at the start of each update.
+    ///
+    /// The default is a [`StdClock`](crate::StdClock) if the `std` feature
+    /// is enabled, or no clock otherwise, in which case time only advances
+    /// with [`advance_time`](Self::advance_time).  Use a
+    /// [`ManualClock`](crate::ManualClock) to test time based watch
+    /// functions without waiting.
+    pub fn set_clock<C>(&mut self, clock: C)
+    where
+        C: 'static + Clock,
+    {
+        self.timers.set_clock(Box::new(clock));
+    }
+
+    /// Set the time of this context's clock, triggering any timers which
+    /// are due, so that they run on the next update.  The time is measured
+    /// from an arbitrary point chosen by the caller, and never goes
+    /// backwards.
+    ///
+    /// This removes the clock of the context, so the time only advances
+    /// with calls to this function, until another clock is set with
+    /// [`set_clock`](Self::set_clock).
+    pub fn advance_time(&mut self, now: Duration) {
+        self.timers.advance_to(now);
+    }
+
+    /// Get the earliest time, according to this context's clock, at which
+    /// a timer read by a watch function is due.  Update the context at or
+    /// after that time to run the watch functions which are waiting for
+    /// it.
+    pub fn next_deadline(&self) -> Option<Duration> {
+        self.timers.next_deadline()
+    }
+
+    /// Returns true if a call to [`update`](Self::update) would have any
+    /// work to do.
+    pub(crate) fn has_pending_updates(&self) -> bool {
+        if self.sync_context.has_pending() {
+            return true;
+        }
+        if self.timers.clock_timeout() == Some(Duration::ZERO) {
+            return true;
+        }
+        let frame = self.next_frame.take();
+        let pending = !frame.is_empty();
+        self.next_frame.set(frame);
+        pending
+    }
+
     /*
     pub(crate) fn channels_context(&self) -> &ChannelsContext {
         &self.chan_ctx
@@ -234,6 +469,142 @@ impl<'ctx, O: ?Sized> WatchContext<'ctx, O> {
     */
 }
 
+#[cfg(feature = "std")]
+impl<'ctx, O: ?Sized> WatchContext<'ctx, O> {
+    /// Block the current thread until this context has updates to process,
+    /// such as when a [`SyncTrigger`](crate::SyncTrigger) for it fires, a
+    /// function is posted through a [`ContextHandle`], or a timer is due
+    /// according to the context's clock.  Only a clock which is
+    /// [real time](crate::Clock::is_real_time), such as the default
+    /// [`StdClock`](crate::StdClock), wakes this thread when a timer is due.
+    ///
+    /// Returns true if there are updates pending, or false if the timeout
+    /// elapsed first.  A timeout of `None` waits indefinitely.
+    pub fn wait_for_sync(&self, timeout: Option<Duration>) -> bool {
+        struct ThreadWaker(std::thread::Thread);
+
+        impl std::task::Wake for ThreadWaker {
+            fn wake(self: std::sync::Arc<Self>) {
+                self.0.unpark();
+            }
+        }
+
+        // a timeout too long to represent is treated as indefinite
+        let deadline = timeout.and_then(|t| Instant::now().checked_add(t));
+        let waker = task::Waker::from(std::sync::Arc::new(ThreadWaker(
+            std::thread::current(),
+        )));
+        let previous = self.sync_context.take_waker();
+        let ready = loop {
+            self.sync_context.register_waker(&waker);
+            if self.has_pending_updates() {
+                break true;
+            }
+            // a clock which doesn't follow the system's may never reach
+            // the deadline, so it's only waited on by calling update
+            let timer_deadline = self
+                .timers
+                .sleep_timeout()
+                .and_then(|t| Instant::now().checked_add(t));
+            let deadline = match (deadline, timer_deadline) {
+                (Some(a), Some(b)) => Some(a.min(b)),
+                (a, b) => a.or(b),
+            };
+            match deadline {
+                None => std::thread::park(),
+                Some(deadline) => {
+                    let now = Instant::now();
+                    if now >= deadline {
+                        break false;
+                    }
+                    std::thread::park_timeout(deadline - now);
+                }
+            }
+        };
+        // put back the waker of any task polling this context, which was
+        // replaced by the waker of this thread
+        self.sync_context.take_waker();
+        if let Some(previous) = previous {
+            self.sync_context.register_waker(&previous);
+            if self.has_pending_updates() {
+                previous.wake();
+            }
+        }
+        ready
+    }
+
+    /// Get a file descriptor which becomes readable whenever this context
+    /// has updates to process, such as when a
+    /// [`SyncTrigger`](crate::SyncTrigger) for it fires or a function is
+    /// posted through a [`ContextHandle`].  Calling
+    /// [`update`](Self::update) makes it no longer readable.
+    ///
+    /// This allows the context to be driven by an event loop built on
+    /// `epoll` or similar.  The descriptor is an eventfd, created the first
+    /// time this is called, and closed when the context is dropped.  It is
+    /// only available when building for Linux with the `linux` feature.
+    #[cfg(all(feature = "linux", target_os = "linux"))]
+    pub fn sync_fd(&self) -> std::io::Result<std::os::fd::BorrowedFd<'_>> {
+        self.sync_context.sync_fd()
+    }
+
+    /// Repeatedly update this context, waiting for updates in between, until
+    /// the predicate returns true.
+    pub fn run_until<F>(&mut self, mut predicate: F)
+    where
+        F: FnMut(&mut O) -> bool,
+    {
+        loop {
+            self.update();
+            if predicate(&mut self.owner) {
+                break;
+            }
+            self.wait_for_sync(None);
+        }
+    }
+}
+
+/// Future returned by [`WatchContext::updates_pending`].
+pub struct UpdatesPending<'a, 'ctx, O: ?Sized> {
+    ctx: &'a WatchContext<'ctx, O>,
+}
+
+impl<'a, 'ctx, O: ?Sized> Future for UpdatesPending<'a, 'ctx, O> {
+    type Output = ();
+
+    fn poll(self: Pin<&mut Self>, cx: &mut task::Context<'_>) -> Poll<()> {
+        if self.ctx.poll_pending(cx) {
+            Poll::Ready(())
+        } else {
+            Poll::Pending
+        }
+    }
+}
+
+#[derive(Default)]
+struct UntilState {
+    done: Cell<bool>,
+    waker: Cell<Option<task::Waker>>,
+}
+
+/// Future returned by [`WatchContext::until`].
+pub struct Until {
+    state: Rc<UntilState>,
+}
+
+impl Future for Until {
+    type Output = ();
+
+    fn poll(self: Pin<&mut Self>, cx: &mut task::Context<'_>) -> Poll<()> {
+        if self.state.done.get() {
+            Poll::Ready(())
+        } else {
+            self.state.waker.set(Some(cx.waker().clone()));
+            Poll::Pending
+        }
+    }
+}
+
 impl<'ctx, O: Default> Default for WatchContext<'ctx, O> {
     fn default() -> Self {
         Self::new()
@@ -0,0 +1,280 @@
+/* SPDX-License-Identifier: (Apache-2.0 OR MIT OR Zlib) */
+/* Copyright © 2026 Violet Leonard */
+
+use core::{
+    cell::{Cell, Ref, RefCell},
+    fmt,
+    time::Duration,
+};
+
+use crate::{timer::TimerState, WatchArg};
+
+#[cfg(feature = "std")]
+use crate::DefaultOwner;
+
+/// Decides when a debounced watch function runs: immediately the first
+/// time, then once `delay` has passed since it was last triggered.
+pub(crate) struct DebounceGate {
+    state: TimerState,
+    delay: Duration,
+    started: Cell<bool>,
+}
+
+impl DebounceGate {
+    pub fn new(delay: Duration) -> Self {
+        Self {
+            state: TimerState::default(),
+            delay,
+            started: Cell::new(false),
+        }
+    }
+
+    pub fn should_run<O: ?Sized>(&self, ctx: WatchArg<'_, '_, O>) -> bool {
+        // the function isn't run while waiting, so it can't read the values
+        // it depends on again; they must stay watched instead
+        ctx.watch.set_sticky();
+        self.state.bind_clock(ctx);
+        let now = match self.state.now() {
+            Some(now) if self.started.replace(true) => now,
+            _ => return true,
+        };
+        match self.state.start.get() {
+            // re-run by the deadline, with no changes since
+            Some(last_change) if now - last_change >= self.delay => {
+                self.state.stop();
+                true
+            }
+            // every change pushes the deadline back
+            _ => {
+                ctx.watch.skip_run();
+                self.state.start.set(Some(now));
+                self.state.watched(ctx);
+                self.state.schedule(self.delay);
+                false
+            }
+        }
+    }
+}
+
+/// Decides when a throttled watch function runs: immediately, unless it
+/// already ran less than `period` ago, in which case it runs once that
+/// period is over.
+pub(crate) struct ThrottleGate {
+    state: TimerState,
+    period: Duration,
+}
+
+impl ThrottleGate {
+    pub fn new(period: Duration) -> Self {
+        Self {
+            state: TimerState::default(),
+            period,
+        }
+    }
+
+    pub fn should_run<O: ?Sized>(&self, ctx: WatchArg<'_, '_, O>) -> bool {
+        self.state.bind_clock(ctx);
+        let now = match self.state.now() {
+            Some(now) => now,
+            None => return true,
+        };
+        match self.state.start.get() {
+            Some(last_run) if now - last_run < self.period => {
+                self.state.watched(ctx);
+                self.state.schedule(self.period);
+                false
+            }
+            _ => {
+                self.state.stop();
+                self.state.start.set(Some(now));
+                true
+            }
+        }
+    }
+}
+
+/// A watched value whose changes only become visible once it has been left
+/// alone for a period of time, according to the clock of the context.
+///
+/// Each call to [`set`](Self::set) stores a pending value and restarts the
+/// quiet period.  Watch functions which read the value see the previous
+/// one until a full period passes without another call to `set`, and are
+/// then re-run with the newest value.  The quiet period is measured using
+/// the time of the context's most recent update.
+///
+/// # Examples
+/// ```rust
+///# use core::time::Duration;
+///# use drying_paint::*;
+/// struct Owner {
+///     search: Debounced<&'static str>,
+///     searched_for: Vec<&'static str>,
+/// }
+///
+/// let clock = ManualClock::new();
+/// let mut ctx = WatchContext::from_owner(Owner {
+///     search: Debounced::new("", Duration::from_millis(300)),
+///     searched_for: Vec::new(),
+/// });
+/// ctx.set_clock(clock.clone());
+/// ctx.add_watch(|owner, arg| {
+///     let search = *owner.search.get(arg);
+///     owner.searched_for.push(search);
+/// });
+/// for text in ["p", "pa", "pai", "pain", "paint"] {
+///     ctx.owner().search.set(text);
+///     clock.advance(Duration::from_millis(100));
+///     ctx.update();
+/// }
+/// clock.advance(Duration::from_millis(300));
+/// ctx.update();
+/// assert_eq!(ctx.owner().searched_for, ["", "paint"]);
+/// ```
+pub struct Debounced<T> {
+    state: TimerState,
+    delay: Duration,
+    value: RefCell<T>,
+    pending: RefCell<Option<T>>,
+}
+
+impl<T: fmt::Debug> fmt::Debug for Debounced<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Debounced")
+            .field("delay", &self.delay)
+            .field("value", &self.value)
+            .field("pending", &self.pending)
+            .finish()
+    }
+}
+
+impl<T> Debounced<T> {
+    /// Create a new debounced value, which waits for `delay` to pass
+    /// without changes before they are visible.
+    pub fn new(value: T, delay: Duration) -> Self {
+        Self {
+            state: TimerState::default(),
+            delay,
+            value: RefCell::new(value),
+            pending: RefCell::new(None),
+        }
+    }
+
+    /// Get the delay this value waits for.
+    pub fn delay(&self) -> Duration {
+        self.delay
+    }
+
+    /// Store a new value, which becomes visible once the value has not been
+    /// set again for the delay.
+    pub fn set(&mut self, value: T) {
+        *self.pending.get_mut() = Some(value);
+        match self.state.now() {
+            Some(now) => {
+                self.state.start.set(Some(now));
+                self.state.schedule(self.delay);
+            }
+            // not read by any watch function yet, so start the delay once
+            // it is
+            None => self.state.restart(),
+        }
+    }
+
+    /// Get the value, binding a watch closure.
+    pub fn get<O: ?Sized>(&self, ctx: WatchArg<'_, '_, O>) -> Ref<'_, T> {
+        self.state.watched(ctx);
+        if self.pending.borrow().is_some() {
+            let due = match self.state.elapsed(ctx) {
+                Some(elapsed) => elapsed >= self.delay,
+                None => true,
+            };
+            if due {
+                self.state.stop();
+                if let Some(value) = self.pending.borrow_mut().take() {
+                    *self.value.borrow_mut() = value;
+                }
+            } else {
+                self.state.schedule(self.delay);
+            }
+        }
+        self.get_unwatched()
+    }
+
+    /// Get the value which is currently visible, without binding any watch
+    /// closure.
+    pub fn get_unwatched(&self) -> Ref<'_, T> {
+        self.value.borrow()
+    }
+
+    /// Returns true if a value has been set which is not visible yet.
+    pub fn is_pending(&self) -> bool {
+        self.pending.borrow().is_some()
+    }
+}
+
+#[cfg(feature = "std")]
+impl<T> Debounced<T> {
+    pub fn get_auto(&self) -> Ref<'_, T> {
+        WatchArg::<'_, 'static, DefaultOwner>::try_with_current(|arg| {
+            drop(self.get(arg));
+        });
+        self.get_unwatched()
+    }
+}
+
+#[cfg(all(test, feature = "std"))]
+mod tests {
+    use std::{cell::RefCell, rc::Rc, time::Duration, vec::Vec};
+
+    use crate::*;
+
+    #[derive(Default)]
+    struct Content {
+        input: Watched<u32>,
+        debounced: Vec<u32>,
+        throttled: Vec<u32>,
+    }
+
+    impl Watcher<'static> for Content {
+        fn init(mut init: impl WatcherInit<'static, Self>) {
+            init.watch_debounced(Duration::from_millis(100), |root| {
+                let input = *root.input;
+                root.debounced.push(input);
+            });
+            init.watch_throttled(Duration::from_millis(30), |root| {
+                let input = *root.input;
+                root.throttled.push(input);
+            });
+        }
+    }
+
+    #[test]
+    fn debounced_and_throttled() {
+        let ms = Duration::from_millis;
+        let clock = ManualClock::new();
+        let content = Rc::new(RefCell::new(Content::default()));
+        let mut ctx = WatchContext::new();
+        ctx.set_clock(clock.clone());
+        ctx.add_watcher(&Rc::downgrade(&content));
+        for input in 1..=5 {
+            clock.advance(ms(20));
+            *content.borrow_mut().input = input;
+            ctx.update();
+        }
+        assert_eq!(content.borrow().debounced, [0]);
+        assert_eq!(content.borrow().throttled, [0, 2, 4]);
+        assert_eq!(ctx.next_deadline(), Some(ms(110)));
+        clock.set(ms(120));
+        ctx.update();
+        assert_eq!(content.borrow().throttled, [0, 2, 4, 5]);
+        // the last input was at 100ms, so the debounce waits until 200ms
+        assert_eq!(ctx.next_deadline(), Some(ms(200)));
+        clock.set(ms(199));
+        ctx.update();
+        assert_eq!(content.borrow().debounced, [0]);
+        clock.set(ms(200));
+        ctx.update();
+        assert_eq!(content.borrow().debounced, [0, 5]);
+        assert_eq!(content.borrow().throttled, [0, 2, 4, 5]);
+        assert_eq!(ctx.next_deadline(), None);
+    }
+}
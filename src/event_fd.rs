@@ -0,0 +1,97 @@
+/* SPDX-License-Identifier: (Apache-2.0 OR MIT OR Zlib) */
+/* Copyright © 2026 Violet Leonard */
+
+use std::{
+    io,
+    os::fd::{BorrowedFd, RawFd},
+    sync::atomic::{AtomicI32, Ordering},
+};
+
+const NO_FD: RawFd = -1;
+
+/// An eventfd which is created the first time it is asked for, and from
+/// then on is signalled along with the rest of a `SyncFlag`.
+pub(crate) struct EventFd {
+    fd: AtomicI32,
+}
+
+impl Default for EventFd {
+    fn default() -> Self {
+        Self {
+            fd: AtomicI32::new(NO_FD),
+        }
+    }
+}
+
+impl Drop for EventFd {
+    fn drop(&mut self) {
+        let fd = *self.fd.get_mut();
+        if fd != NO_FD {
+            unsafe {
+                libc::close(fd);
+            }
+        }
+    }
+}
+
+impl EventFd {
+    /// Get the file descriptor, creating it if needed.  Returns true along
+    /// with it if it was just created.
+    pub fn get_or_create(&self) -> io::Result<(BorrowedFd<'_>, bool)> {
+        let mut fd = self.fd.load(Ordering::Acquire);
+        let mut created = false;
+        if fd == NO_FD {
+            let new_fd = unsafe {
+                libc::eventfd(0, libc::EFD_CLOEXEC | libc::EFD_NONBLOCK)
+            };
+            if new_fd < 0 {
+                return Err(io::Error::last_os_error());
+            }
+            fd = match self.fd.compare_exchange(
+                NO_FD,
+                new_fd,
+                Ordering::AcqRel,
+                Ordering::Acquire,
+            ) {
+                Ok(_) => {
+                    created = true;
+                    new_fd
+                }
+                Err(existing) => {
+                    unsafe {
+                        libc::close(new_fd);
+                    }
+                    existing
+                }
+            };
+        }
+        // the fd is only closed when self is dropped
+        Ok((unsafe { BorrowedFd::borrow_raw(fd) }, created))
+    }
+
+    /// Make the file descriptor readable, if it has been created.
+    pub fn signal(&self) {
+        let fd = self.fd.load(Ordering::Acquire);
+        if fd != NO_FD {
+            let value: u64 = 1;
+            // this can only fail if the counter would overflow, in which
+            // case it is already readable
+            unsafe {
+                libc::write(fd, (&value as *const u64).cast(), 8);
+            }
+        }
+    }
+
+    /// Make the file descriptor no longer readable, until it is signalled
+    /// again.
+    pub fn reset(&self) {
+        let fd = self.fd.load(Ordering::Acquire);
+        if fd != NO_FD {
+            let mut value: u64 = 0;
+            // fails with EAGAIN if it was not signalled, which is fine
+            unsafe {
+                libc::read(fd, (&mut value as *mut u64).cast(), 8);
+            }
+        }
+    }
+}
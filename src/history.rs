@@ -0,0 +1,428 @@
+/* SPDX-License-Identifier: (Apache-2.0 OR MIT OR Zlib) */
+/* Copyright © 2026 Violet Leonard */
+
+use {alloc::vec::Vec, core::fmt};
+
+use crate::{DefaultOwner, WatchArg, WatchedMeta};
+
+#[derive(Clone, Copy)]
+enum Step {
+    Undo,
+    Redo,
+}
+
+/// A watched value which remembers its previous values, so that changes
+/// to it can be undone and redone.
+///
+/// Every mutation records the value from before it as one undo step, and
+/// forgets anything which could have been redone.  Mutations made between
+/// [`begin_group`](Self::begin_group) and [`end_group`](Self::end_group)
+/// are recorded as a single step.  To undo changes across many fields
+/// together, keep them in one struct inside a single `WatchedHistory`.
+///
+/// Whether there is anything to undo or redo is watched separately from
+/// the value, so watch functions which only read
+/// [`can_undo`](Self::can_undo) or [`can_redo`](Self::can_redo) are
+/// re-run only when those change.
+///
+/// # Examples
+/// ```rust
+///# use drying_paint::*;
+/// struct Owner {
+///     text: WatchedHistory<'static, String, Owner>,
+///     undo_enabled: bool,
+/// }
+///
+/// let mut ctx = WatchContext::from_owner(Owner {
+///     text: WatchedHistory::new(String::new()),
+///     undo_enabled: false,
+/// });
+/// ctx.add_watch(|owner, arg| {
+///     owner.undo_enabled = owner.text.can_undo(arg);
+/// });
+/// ctx.owner().text.begin_group();
+/// ctx.owner().text.get_mut_external().push_str("drying");
+/// ctx.owner().text.get_mut_external().push_str(" paint");
+/// ctx.owner().text.end_group();
+/// ctx.update();
+/// assert!(ctx.owner().undo_enabled);
+/// assert!(ctx.owner().text.undo_external());
+/// ctx.update();
+/// assert!(ctx.owner().text.get_unwatched().is_empty());
+/// assert!(!ctx.owner().undo_enabled);
+/// ```
+pub struct WatchedHistory<'ctx, T, O: ?Sized = DefaultOwner> {
+    meta: WatchedMeta<'ctx, O>,
+    steps_meta: WatchedMeta<'ctx, O>,
+    value: T,
+    undo: Vec<T>,
+    redo: Vec<T>,
+    group_depth: usize,
+    group_recorded: bool,
+}
+
+impl<'ctx, T: fmt::Debug, O: ?Sized> fmt::Debug
+    for WatchedHistory<'ctx, T, O>
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("WatchedHistory")
+            .field("value", &self.value)
+            .field("undo", &self.undo)
+            .field("redo", &self.redo)
+            .finish()
+    }
+}
+
+impl<'ctx, T: Default, O: ?Sized> Default for WatchedHistory<'ctx, T, O> {
+    fn default() -> Self {
+        Self::new(T::default())
+    }
+}
+
+impl<'ctx, T, O: ?Sized> From<T> for WatchedHistory<'ctx, T, O> {
+    fn from(value: T) -> Self {
+        Self::new(value)
+    }
+}
+
+impl<'ctx, T, O: ?Sized> WatchedHistory<'ctx, T, O> {
+    /// Create a new watched value, with nothing to undo.
+    pub fn new(value: T) -> Self {
+        Self {
+            meta: WatchedMeta::new(),
+            steps_meta: WatchedMeta::new(),
+            value,
+            undo: Vec::new(),
+            redo: Vec::new(),
+            group_depth: 0,
+            group_recorded: false,
+        }
+    }
+
+    /// Consumes the `WatchedHistory`, returning the current value.
+    pub fn into_inner(self) -> T {
+        self.value
+    }
+
+    /// Get a reference to the current value, binding a watch closure.
+    pub fn get(&self, ctx: WatchArg<'_, 'ctx, O>) -> &T {
+        self.meta.watched(ctx);
+        &self.value
+    }
+
+    /// Get a reference to the current value, without binding any watch
+    /// closure.
+    pub fn get_unwatched(&self) -> &T {
+        &self.value
+    }
+
+    /// Returns true if there is a change which can be undone, binding a
+    /// watch closure.
+    pub fn can_undo(&self, ctx: WatchArg<'_, 'ctx, O>) -> bool {
+        self.steps_meta.watched(ctx);
+        !self.undo.is_empty()
+    }
+
+    /// Returns true if there is an undone change which can be redone,
+    /// binding a watch closure.
+    pub fn can_redo(&self, ctx: WatchArg<'_, 'ctx, O>) -> bool {
+        self.steps_meta.watched(ctx);
+        !self.redo.is_empty()
+    }
+
+    /// Start a group of mutations which are undone together.  Groups may
+    /// be nested, in which case the step ends with the outermost group.
+    pub fn begin_group(&mut self) {
+        if self.group_depth == 0 {
+            self.group_recorded = false;
+        }
+        self.group_depth += 1;
+    }
+
+    /// End a group of mutations started with
+    /// [`begin_group`](Self::begin_group).
+    pub fn end_group(&mut self) {
+        self.group_depth = self.group_depth.saturating_sub(1);
+    }
+
+    /// Forget every step which could be undone or redone, notifying
+    /// watchers of `can_undo` and `can_redo` if that changes anything.
+    #[cfg_attr(do_cycle_debug, track_caller)]
+    pub fn clear_history(&mut self, ctx: WatchArg<'_, 'ctx, O>) {
+        if self.clear_steps() {
+            self.steps_meta.trigger(ctx);
+        }
+    }
+
+    #[cfg_attr(do_cycle_debug, track_caller)]
+    pub fn clear_history_external(&mut self) {
+        if self.clear_steps() {
+            self.steps_meta.trigger_external();
+        }
+    }
+
+    /// Restore the value from before the most recent step, returning false
+    /// if there was nothing to undo.
+    #[cfg_attr(do_cycle_debug, track_caller)]
+    pub fn undo(&mut self, ctx: WatchArg<'_, 'ctx, O>) -> bool {
+        self.step(Step::Undo, Some(ctx))
+    }
+
+    #[cfg_attr(do_cycle_debug, track_caller)]
+    pub fn undo_external(&mut self) -> bool {
+        self.step(Step::Undo, None)
+    }
+
+    /// Restore the value from before the most recent undo, returning false
+    /// if there was nothing to redo.
+    #[cfg_attr(do_cycle_debug, track_caller)]
+    pub fn redo(&mut self, ctx: WatchArg<'_, 'ctx, O>) -> bool {
+        self.step(Step::Redo, Some(ctx))
+    }
+
+    #[cfg_attr(do_cycle_debug, track_caller)]
+    pub fn redo_external(&mut self) -> bool {
+        self.step(Step::Redo, None)
+    }
+
+    fn steps(&self) -> (bool, bool) {
+        (!self.undo.is_empty(), !self.redo.is_empty())
+    }
+
+    /// Returns true if this changed whether there is anything to undo or
+    /// redo.
+    fn clear_steps(&mut self) -> bool {
+        let before = self.steps();
+        self.undo.clear();
+        self.redo.clear();
+        self.group_recorded = false;
+        before != self.steps()
+    }
+
+    /// Move the current value onto one stack, replacing it with the top of
+    /// the other, and notify watchers.  Returns false if there was nothing
+    /// to move.
+    #[cfg_attr(do_cycle_debug, track_caller)]
+    fn step(
+        &mut self,
+        step: Step,
+        ctx: Option<WatchArg<'_, 'ctx, O>>,
+    ) -> bool {
+        let (from, to) = match step {
+            Step::Undo => (&mut self.undo, &mut self.redo),
+            Step::Redo => (&mut self.redo, &mut self.undo),
+        };
+        let previous = match from.pop() {
+            Some(previous) => previous,
+            None => return false,
+        };
+        to.push(core::mem::replace(&mut self.value, previous));
+        let steps_changed = from.is_empty() || to.len() == 1;
+        self.group_recorded = false;
+        match ctx {
+            Some(ctx) => {
+                self.meta.trigger(ctx);
+                if steps_changed {
+                    self.steps_meta.trigger(ctx);
+                }
+            }
+            None => {
+                self.meta.trigger_external();
+                if steps_changed {
+                    self.steps_meta.trigger_external();
+                }
+            }
+        }
+        true
+    }
+}
+
+impl<'ctx, T: Clone, O: ?Sized> WatchedHistory<'ctx, T, O> {
+    /// Replace the value, recording the previous one as an undo step, and
+    /// notify watchers that the value has changed.
+    #[cfg_attr(do_cycle_debug, track_caller)]
+    pub fn set(&mut self, value: T, ctx: WatchArg<'_, 'ctx, O>) {
+        *self.get_mut(ctx) = value;
+    }
+
+    #[cfg_attr(do_cycle_debug, track_caller)]
+    pub fn set_external(&mut self, value: T) {
+        *self.get_mut_external() = value;
+    }
+
+    /// Get a mutable reference to the value, recording the current one as
+    /// an undo step, and notify watchers that the value has changed.
+    #[cfg_attr(do_cycle_debug, track_caller)]
+    pub fn get_mut(&mut self, ctx: WatchArg<'_, 'ctx, O>) -> &mut T {
+        if self.record() {
+            self.steps_meta.trigger(ctx);
+        }
+        self.meta.trigger(ctx);
+        self.meta.watched(ctx);
+        &mut self.value
+    }
+
+    #[cfg_attr(do_cycle_debug, track_caller)]
+    pub fn get_mut_external(&mut self) -> &mut T {
+        if self.record() {
+            self.steps_meta.trigger_external();
+        }
+        self.meta.trigger_external();
+        &mut self.value
+    }
+
+    /// Record the current value as an undo step, unless one was already
+    /// recorded for the current group.  Returns true if this changed
+    /// whether there is anything to undo or redo.
+    fn record(&mut self) -> bool {
+        let before = self.steps();
+        if self.group_depth == 0 || !self.group_recorded {
+            self.undo.push(self.value.clone());
+            self.group_recorded = self.group_depth > 0;
+        }
+        self.redo.clear();
+        before != self.steps()
+    }
+}
+
+#[cfg(feature = "std")]
+impl<T> WatchedHistory<'static, T, DefaultOwner> {
+    pub fn get_auto(&self) -> &T {
+        self.meta.watched_auto();
+        &self.value
+    }
+
+    pub fn can_undo_auto(&self) -> bool {
+        self.steps_meta.watched_auto();
+        !self.undo.is_empty()
+    }
+
+    pub fn can_redo_auto(&self) -> bool {
+        self.steps_meta.watched_auto();
+        !self.redo.is_empty()
+    }
+
+    #[cfg_attr(do_cycle_debug, track_caller)]
+    pub fn clear_history_auto(&mut self) {
+        if self.clear_steps() {
+            self.steps_meta.trigger_auto();
+        }
+    }
+
+    #[cfg_attr(do_cycle_debug, track_caller)]
+    pub fn undo_auto(&mut self) -> bool {
+        self.step_auto(Step::Undo)
+    }
+
+    #[cfg_attr(do_cycle_debug, track_caller)]
+    pub fn redo_auto(&mut self) -> bool {
+        self.step_auto(Step::Redo)
+    }
+
+    #[cfg_attr(do_cycle_debug, track_caller)]
+    fn step_auto(&mut self, step: Step) -> bool {
+        let mut stepped = None;
+        WatchArg::try_with_current(|arg| {
+            stepped = Some(self.step(step, Some(arg)));
+        });
+        stepped.unwrap_or_else(|| self.step(step, None))
+    }
+}
+
+#[cfg(feature = "std")]
+impl<T: Clone> WatchedHistory<'static, T, DefaultOwner> {
+    #[cfg_attr(do_cycle_debug, track_caller)]
+    pub fn set_auto(&mut self, value: T) {
+        *self.get_mut_auto() = value;
+    }
+
+    #[cfg_attr(do_cycle_debug, track_caller)]
+    pub fn get_mut_auto(&mut self) -> &mut T {
+        if self.record() {
+            self.steps_meta.trigger_auto();
+        }
+        self.meta.trigger_auto();
+        self.meta.watched_auto();
+        &mut self.value
+    }
+}
+
+#[cfg(all(test, feature = "std"))]
+mod tests {
+    use std::{cell::RefCell, rc::Rc};
+
+    use crate::*;
+
+    #[derive(Default)]
+    struct Content {
+        value: WatchedHistory<'static, i32>,
+        seen: i32,
+        buttons: (bool, bool),
+        button_runs: u32,
+    }
+
+    impl Watcher<'static> for Content {
+        fn init(mut init: impl WatcherInit<'static, Self>) {
+            init.watch(|root| {
+                root.seen = *root.value.get_auto();
+            });
+            init.watch(|root| {
+                root.buttons =
+                    (root.value.can_undo_auto(), root.value.can_redo_auto());
+                root.button_runs += 1;
+            });
+        }
+    }
+
+    #[test]
+    fn undo_redo_groups() {
+        let content = Rc::new(RefCell::new(Content::default()));
+        let mut ctx = WatchContext::new();
+        ctx.add_watcher(&Rc::downgrade(&content));
+        assert_eq!(content.borrow().button_runs, 1);
+        content.borrow_mut().value.set_external(1);
+        ctx.update();
+        content.borrow_mut().value.set_external(2);
+        ctx.update();
+        assert_eq!(content.borrow().seen, 2);
+        assert_eq!(content.borrow().buttons, (true, false));
+        // the second change did not change which buttons are enabled
+        assert_eq!(content.borrow().button_runs, 2);
+
+        {
+            let mut content = content.borrow_mut();
+            content.value.begin_group();
+            content.value.set_external(3);
+            content.value.begin_group();
+            *content.value.get_mut_external() += 1;
+            content.value.end_group();
+            content.value.set_external(5);
+            content.value.end_group();
+        }
+        ctx.update();
+        assert_eq!(content.borrow().seen, 5);
+        assert!(content.borrow_mut().value.undo_external());
+        ctx.update();
+        assert_eq!(content.borrow().seen, 2);
+        assert_eq!(content.borrow().buttons, (true, true));
+        assert!(content.borrow_mut().value.undo_external());
+        assert!(content.borrow_mut().value.undo_external());
+        assert!(!content.borrow_mut().value.undo_external());
+        ctx.update();
+        assert_eq!(content.borrow().seen, 0);
+        assert_eq!(content.borrow().buttons, (false, true));
+        assert!(content.borrow_mut().value.redo_external());
+        ctx.update();
+        assert_eq!(content.borrow().seen, 1);
+
+        // a new change forgets what could have been redone
+        content.borrow_mut().value.set_external(7);
+        ctx.update();
+        assert_eq!(content.borrow().buttons, (true, false));
+        assert!(!content.borrow_mut().value.redo_external());
+        content.borrow_mut().value.clear_history_external();
+        ctx.update();
+        assert_eq!(content.borrow().buttons, (false, false));
+        assert_eq!(content.borrow().seen, 7);
+    }
+}
@@ -60,34 +60,228 @@ extern crate std;
 
 extern crate alloc;
 
+mod atomic;
+mod changes;
+mod clock;
 mod context;
 #[cfg(do_cycle_debug)]
 mod cycle_debug;
+mod debounce;
+#[cfg(all(feature = "linux", target_os = "linux"))]
+mod event_fd;
+mod fnv;
+mod history;
 mod queue;
 mod sync;
+mod timer;
 mod trigger;
+mod watch_async;
 mod watched_core;
 mod watcher;
 
+#[cfg(target_has_atomic = "64")]
+pub use crate::atomic::{SyncWatchedAtomicI64, SyncWatchedAtomicU64};
 pub use crate::{
-    context::{DefaultOwner, WatchContext},
+    atomic::{
+        SyncWatchedAtomicBool, SyncWatchedAtomicI16, SyncWatchedAtomicI32,
+        SyncWatchedAtomicI8, SyncWatchedAtomicIsize, SyncWatchedAtomicU16,
+        SyncWatchedAtomicU32, SyncWatchedAtomicU8, SyncWatchedAtomicUsize,
+    },
+    changes::Changes,
+    clock::{Clock, ManualClock},
+    context::{DefaultOwner, Until, UpdatesPending, WatchContext},
+    debounce::Debounced,
+    history::WatchedHistory,
     queue::WatchedQueue,
     sync::{
-        watched_channel, SendGuard, SyncTrigger, SyncWatchedMeta,
-        WatchedReceiver, WatchedSender,
+        watched_channel, ContextHandle, SendGuard, SyncTrigger,
+        SyncWatchedMeta, WatchedReceiver, WatchedSender,
     },
+    timer::{WatchedInterval, WatchedTimer},
     trigger::{RawWatchArg, WatchArg, WatchName},
     watched_core::{
         WatchedCellCore, WatchedCore, WatchedMeta, WatchedValueCore,
     },
-    watcher::{Watcher, WatcherHolder, WatcherInit},
+    watcher::{OwnerPath, Watcher, WatcherHolder, WatcherInit},
 };
 
+#[cfg(feature = "std")]
+pub use crate::clock::StdClock;
+
 #[cfg(feature = "std")]
 mod watched;
 #[cfg(feature = "std")]
 pub use crate::watched::{Watched, WatchedCell, WatchedValue};
 
+#[cfg(feature = "std")]
+mod resource;
+#[cfg(feature = "std")]
+pub use crate::resource::{ResourceJob, ResourceState, WatchedResource};
+
+#[cfg(feature = "std")]
+mod worker;
+#[cfg(feature = "std")]
+pub use crate::worker::{WatchedComputed, WorkerPool};
+
+#[cfg(feature = "std")]
+mod sync_watched;
+#[cfg(feature = "std")]
+pub use crate::sync_watched::{
+    sync_watched, SyncWatched, SyncWatchedGuard, SyncWatchedSetter,
+};
+
+#[cfg(feature = "send")]
+mod send;
+#[cfg(feature = "send")]
+pub use crate::send::SendWatchContextBuilder;
+
+/// Implement [`Watcher`](trait@Watcher) for a struct.
+///
+/// Fields marked `#[watcher]` are initialized as children with
+/// [`WatcherInit::init_child`].  Methods marked `#[watch]` inside an impl
+/// block marked [`#[watches]`](watches) become watch functions: a method
+/// taking only `&mut self` (or `&self`) uses the automatic API of
+/// [`WatcherInit::watch`], and one which also takes a [`WatchArg`] uses
+/// [`WatcherInit::watch_explicit`].
+///
+/// By default, `Watcher<'static, DefaultOwner>` is implemented.  Use
+/// `#[watcher(ctx = 'a, owner = Type)]` on the struct, and the same
+/// arguments to `#[watches]`, to implement it for another context
+/// lifetime or owner.
+///
+/// # Examples
+/// ```rust
+///# use std::{rc::Rc, cell::RefCell};
+///# use drying_paint::*;
+/// #[derive(Default, Watcher)]
+/// struct Label {
+///     text: Watched<String>,
+///     width: usize,
+/// }
+///
+/// #[watches]
+/// impl Label {
+///     #[watch]
+///     fn measure(&mut self) {
+///         self.width = self.text.len();
+///     }
+/// }
+///
+/// #[derive(Default, Watcher)]
+/// struct Button {
+///     #[watcher]
+///     label: Label,
+///     clicks: Watched<u32>,
+/// }
+///
+/// #[watches]
+/// impl Button {
+///     #[watch]
+///     fn relabel(&mut self) {
+///         *self.label.text = format!("clicked {} times", *self.clicks);
+///     }
+/// }
+///
+/// let button = Rc::new(RefCell::new(Button::default()));
+/// let mut ctx = WatchContext::new();
+/// ctx.add_watcher(&Rc::downgrade(&button));
+/// ctx.update();
+/// assert_eq!(button.borrow().label.width, 15);
+/// *button.borrow_mut().clicks += 10;
+/// ctx.update();
+/// assert_eq!(button.borrow().label.width, 16);
+/// ```
+#[cfg(feature = "derive")]
+pub use drying_paint_derive::Watcher;
+
+/// Collect the methods marked `#[watch]` in an impl block, to be set up by
+/// [`#[derive(Watcher)]`](macro@Watcher).
+#[cfg(feature = "derive")]
+pub use drying_paint_derive::watches;
+
+/// Generate a companion type for a struct, in which each field is watched
+/// separately.
+///
+/// For a struct `Name`, this generates `WatchedName<'ctx, O = DefaultOwner>`
+/// which holds a `Name`.  For each field `x`, it has methods `get_x(ctx)`
+/// and `set_x(value, ctx)`, along with `get_x_unwatched()`,
+/// `set_x_external(value)`, and with the `std` feature, `get_x_auto()` and
+/// `set_x_auto(value)`.  Watch functions which read one field are not
+/// re-run when another field is set.
+///
+/// # Examples
+/// ```rust
+///# use std::{rc::Rc, cell::RefCell};
+///# use drying_paint::*;
+/// #[derive(Default, WatchedFields)]
+/// struct Size {
+///     width: u32,
+///     height: u32,
+/// }
+///
+/// #[derive(Default)]
+/// struct Layout {
+///     size: WatchedSize<'static>,
+///     width_runs: u32,
+/// }
+///
+/// impl Watcher<'static> for Layout {
+///     fn init(mut init: impl WatcherInit<'static, Self>) {
+///         init.watch(|root| {
+///             root.size.get_width_auto();
+///             root.width_runs += 1;
+///         });
+///     }
+/// }
+///
+/// let layout = Rc::new(RefCell::new(Layout::default()));
+/// let mut ctx = WatchContext::new();
+/// ctx.add_watcher(&Rc::downgrade(&layout));
+/// layout.borrow_mut().size.set_height_external(20);
+/// ctx.update();
+/// assert_eq!(layout.borrow().width_runs, 1);
+/// layout.borrow_mut().size.set_width_external(10);
+/// ctx.update();
+/// assert_eq!(layout.borrow().width_runs, 2);
+/// ```
+#[cfg(feature = "derive")]
+pub use drying_paint_derive::WatchedFields;
+
+#[cfg(feature = "derive")]
+#[doc(hidden)]
+pub mod __private {
+    use crate::WatcherInit;
+
+    /// Used by `#[derive(Watcher)]` for types without `#[watches]`.
+    pub trait NoWatches {
+        fn __drying_paint_watches<'ctx, O, I>(_init: &mut I)
+        where
+            O: ?Sized,
+            I: WatcherInit<'ctx, Self, O>,
+        {
+        }
+    }
+
+    impl<T: ?Sized> NoWatches for T {}
+}
+
+/// Used by `#[derive(WatchedFields)]` for the methods which need `std`.
+#[cfg(all(feature = "derive", feature = "std"))]
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __if_std {
+    ($($item:item)*) => {
+        $($item)*
+    };
+}
+
+#[cfg(all(feature = "derive", not(feature = "std")))]
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __if_std {
+    ($($item:item)*) => {};
+}
+
 #[doc = include_str!("../README.md")]
 #[cfg(doctest)]
 pub struct ReadmeDoctests;
@@ -98,6 +292,16 @@ mod tests {
 
     use super::*;
 
+    struct NoopWaker;
+
+    impl std::task::Wake for NoopWaker {
+        fn wake(self: std::sync::Arc<Self>) {}
+    }
+
+    fn noop_waker() -> std::task::Waker {
+        std::sync::Arc::new(NoopWaker).into()
+    }
+
     #[test]
     fn simple_propogate_core() {
         struct Content {
@@ -194,6 +398,590 @@ mod tests {
         assert_eq!(*content.borrow().value, 43);
     }
 
+    #[test]
+    fn sync_meta_bound_to_one_context() {
+        struct Owner {
+            first: std::sync::Arc<SyncWatchedAtomicU32>,
+            second: SyncWatchedAtomicU32,
+            first_runs: u32,
+        }
+
+        let first = std::sync::Arc::new(SyncWatchedAtomicU32::new(0));
+        let mut other =
+            WatchContext::from_owner(std::sync::Arc::clone(&first));
+        other.add_watch(|first, arg| {
+            first.get(arg);
+        });
+        let mut ctx = WatchContext::from_owner(Owner {
+            first,
+            second: SyncWatchedAtomicU32::new(0),
+            first_runs: 0,
+        });
+        ctx.add_watch(|owner, arg| {
+            owner.second.get(arg);
+        });
+        ctx.add_watch(|owner, arg| {
+            owner.first.get(arg);
+            owner.first_runs += 1;
+        });
+        ctx.update();
+        assert_eq!(ctx.owner().first_runs, 1);
+        // both metas were given the first flag of their contexts
+        ctx.owner().second.store(1);
+        ctx.update();
+        assert_eq!(ctx.owner().first_runs, 1);
+    }
+
+    #[test]
+    fn posted_from_thread() {
+        struct Owner {
+            source: WatchedCore<'static, i32, Owner>,
+            dest: i32,
+        }
+
+        let mut ctx = WatchContext::from_owner(Owner {
+            source: WatchedCore::new(0),
+            dest: 0,
+        });
+        ctx.add_watch(|owner, arg| {
+            owner.dest = *owner.source.get(arg);
+        });
+        let handle = ctx.remote();
+        std::thread::spawn(move || {
+            handle.post(|owner| {
+                *owner.source.get_mut_external() = 17;
+            });
+            handle.post(|owner| {
+                *owner.source.get_mut_external() *= 3;
+            });
+        })
+        .join()
+        .unwrap();
+        assert_eq!(ctx.owner().dest, 0);
+        ctx.update();
+        assert_eq!(ctx.owner().dest, 51);
+    }
+
+    #[test]
+    fn run_until_worker_done() {
+        struct Owner {
+            source: SyncWatched<u32>,
+            dest: u32,
+        }
+
+        let (setter, source) = sync_watched(0);
+        let mut ctx = WatchContext::from_owner(Owner { source, dest: 0 });
+        ctx.add_watch(|owner, arg| {
+            owner.dest = *owner.source.get(arg);
+        });
+        assert!(!ctx.wait_for_sync(Some(std::time::Duration::ZERO)));
+        let thread_handle = std::thread::spawn(move || {
+            for i in 1..=3 {
+                std::thread::sleep(std::time::Duration::from_millis(5));
+                setter.set(i);
+            }
+        });
+        ctx.run_until(|owner| owner.dest == 3);
+        assert_eq!(ctx.owner().dest, 3);
+        thread_handle.join().unwrap();
+    }
+
+    #[test]
+    fn poll_update_woken() {
+        use std::{
+            sync::{atomic, Arc},
+            task::{Context, Poll, Wake, Waker},
+        };
+
+        #[derive(Default)]
+        struct CountWakes(atomic::AtomicUsize);
+
+        impl Wake for CountWakes {
+            fn wake(self: Arc<Self>) {
+                self.0.fetch_add(1, atomic::Ordering::SeqCst);
+            }
+        }
+
+        struct Owner {
+            source: Arc<SyncWatchedAtomicU32>,
+            dest: u32,
+        }
+
+        let mut ctx = WatchContext::from_owner(Owner {
+            source: Arc::default(),
+            dest: 0,
+        });
+        ctx.add_watch(|owner, arg| {
+            owner.dest = owner.source.get(arg);
+        });
+        let wakes = Arc::new(CountWakes::default());
+        let waker = Waker::from(Arc::clone(&wakes));
+        let mut cx = Context::from_waker(&waker);
+        assert_eq!(ctx.poll_update(&mut cx), Poll::Pending);
+        let source = Arc::clone(&ctx.owner().source);
+        std::thread::spawn(move || source.store(9)).join().unwrap();
+        assert_eq!(wakes.0.load(atomic::Ordering::SeqCst), 1);
+        assert_eq!(ctx.poll_update(&mut cx), Poll::Ready(()));
+        assert_eq!(ctx.owner().dest, 9);
+        assert_eq!(ctx.poll_update(&mut cx), Poll::Pending);
+        // blocking on the context must not steal the task's waker
+        assert!(!ctx.wait_for_sync(Some(std::time::Duration::ZERO)));
+        let source = Arc::clone(&ctx.owner().source);
+        std::thread::spawn(move || source.store(10)).join().unwrap();
+        assert_eq!(wakes.0.load(atomic::Ordering::SeqCst), 2);
+    }
+
+    #[test]
+    fn until_connected() {
+        use std::{
+            future::Future,
+            pin::pin,
+            task::{Context, Poll},
+        };
+
+        #[derive(Default)]
+        struct Owner {
+            connected: WatchedCore<'static, bool, Owner>,
+            attempts: u32,
+            greeted: bool,
+        }
+
+        let mut ctx = WatchContext::from_owner(Owner::default());
+        let mut until = pin!(ctx.until(|arg, owner| {
+            owner.attempts += 1;
+            *owner.connected.get(arg)
+        }));
+        ctx.when(
+            |arg, owner| *owner.connected.get(arg),
+            |owner| owner.greeted = true,
+        );
+        let waker = noop_waker();
+        let mut cx = Context::from_waker(&waker);
+        assert_eq!(until.as_mut().poll(&mut cx), Poll::Pending);
+        *ctx.owner().connected.get_mut_external() = true;
+        ctx.update();
+        assert!(ctx.owner().greeted);
+        assert_eq!(until.as_mut().poll(&mut cx), Poll::Ready(()));
+        assert_eq!(ctx.owner().attempts, 2);
+        *ctx.owner().connected.get_mut_external() = false;
+        *ctx.owner().connected.get_mut_external() = true;
+        ctx.update();
+        assert_eq!(ctx.owner().attempts, 2);
+    }
+
+    #[test]
+    fn until_dropped() {
+        #[derive(Default)]
+        struct Owner {
+            connected: WatchedCore<'static, bool, Owner>,
+            attempts: u32,
+        }
+
+        let mut ctx = WatchContext::from_owner(Owner::default());
+        let until = ctx.until(|arg, owner| {
+            owner.attempts += 1;
+            // never satisfied, so only dropping the future stops it
+            let _ = owner.connected.get(arg);
+            false
+        });
+        assert_eq!(ctx.owner().attempts, 1);
+        drop(until);
+        for _ in 0..5 {
+            ctx.owner()
+                .connected
+                .modify_external(|value| *value = !*value);
+            ctx.update();
+        }
+        assert_eq!(ctx.owner().attempts, 1);
+    }
+
+    #[test]
+    fn changes_polled() {
+        use std::task::{Context, Poll};
+
+        #[derive(Default)]
+        struct Owner {
+            name: WatchedCore<'static, &'static str, Owner>,
+        }
+
+        let waker = noop_waker();
+        let mut cx = Context::from_waker(&waker);
+        let mut ctx = WatchContext::from_owner(Owner::default());
+        let mut changes = ctx.changes(|arg, owner| *owner.name.get(arg));
+        assert_eq!(changes.poll_next(&mut cx), Poll::Ready(Some("")));
+        assert_eq!(changes.poll_next(&mut cx), Poll::Pending);
+        *ctx.owner().name.get_mut_external() = "a";
+        *ctx.owner().name.get_mut_external() = "a";
+        ctx.update();
+        *ctx.owner().name.get_mut_external() = "a";
+        ctx.update();
+        assert_eq!(changes.poll_next(&mut cx), Poll::Ready(Some("a")));
+        assert_eq!(changes.poll_next(&mut cx), Poll::Pending);
+        drop(ctx);
+        assert_eq!(changes.poll_next(&mut cx), Poll::Ready(None));
+    }
+
+    #[test]
+    fn changes_end_with_watched_values() {
+        use std::task::{Context, Poll};
+
+        struct Owner {
+            name: Option<WatchedCore<'static, &'static str, Owner>>,
+        }
+
+        let waker = noop_waker();
+        let mut cx = Context::from_waker(&waker);
+        let mut ctx = WatchContext::from_owner(Owner {
+            name: Some(WatchedCore::new("a")),
+        });
+        let mut changes = ctx.changes(|arg, owner| {
+            owner.name.as_ref().map(|name| *name.get(arg))
+        });
+        assert_eq!(changes.poll_next(&mut cx), Poll::Ready(Some(Some("a"))));
+        assert_eq!(changes.poll_next(&mut cx), Poll::Pending);
+        // the context lives on, but nothing can re-run the watch
+        ctx.owner().name = None;
+        ctx.update();
+        assert_eq!(changes.poll_next(&mut cx), Poll::Ready(Some(None)));
+        assert_eq!(changes.poll_next(&mut cx), Poll::Ready(None));
+    }
+
+    #[test]
+    fn combined_watched_values() {
+        #[derive(Default)]
+        struct Content {
+            use_second: WatchedCellCore<'static, bool>,
+            first: WatchedCellCore<'static, u32>,
+            second: WatchedCellCore<'static, u32>,
+            scale: WatchedCellCore<'static, u32>,
+            offset: Option<WatchedCellCore<'static, u32>>,
+            dest: u32,
+            runs: u32,
+        }
+
+        impl Watcher<'static> for Content {
+            fn init(mut init: impl WatcherInit<'static, Self>) {
+                init.watch_explicit(|arg, root| {
+                    root.runs += 1;
+                    let source = (&root.use_second).and_then(|use_second| {
+                        if use_second {
+                            &root.second
+                        } else {
+                            &root.first
+                        }
+                    });
+                    let (value, [scale, _]) =
+                        source.zip([&root.scale, &root.scale]).get(arg);
+                    let offset = root.offset.as_ref().get(arg).unwrap_or(0);
+                    root.dest = value * scale + offset;
+                });
+            }
+        }
+        let content = Rc::new(RefCell::new(Content::default()));
+        content.borrow_mut().first.set_external(2);
+        content.borrow_mut().second.set_external(3);
+        content.borrow_mut().scale.set_external(10);
+        let mut ctx = WatchContext::new();
+        ctx.add_watcher(&Rc::downgrade(&content));
+        assert_eq!(content.borrow().dest, 20);
+        content.borrow_mut().use_second.set_external(true);
+        ctx.update();
+        assert_eq!(content.borrow().dest, 30);
+        assert_eq!(content.borrow().runs, 2);
+        content.borrow_mut().first.set_external(5);
+        ctx.update();
+        assert_eq!(content.borrow().dest, 30);
+        assert_eq!(content.borrow().runs, 2);
+        content.borrow_mut().second.set_external(4);
+        ctx.update();
+        assert_eq!(content.borrow().dest, 40);
+        assert_eq!(content.borrow().runs, 3);
+        content.borrow_mut().offset = Some(WatchedCellCore::new(1));
+        content.borrow_mut().scale.set_external(1);
+        ctx.update();
+        assert_eq!(content.borrow().dest, 5);
+    }
+
+    #[test]
+    fn bound_only_when_changed() {
+        #[derive(Default)]
+        struct Content {
+            source: Watched<u32>,
+            parity: Watched<bool>,
+            downstream_runs: u32,
+        }
+
+        impl Watcher<'static> for Content {
+            fn init(mut init: impl WatcherInit<'static, Self>) {
+                init.bind(
+                    |root| &mut root.parity,
+                    |root| *root.source % 2 == 1,
+                );
+                init.watch(|root| {
+                    let _ = *root.parity;
+                    root.downstream_runs += 1;
+                });
+            }
+        }
+        let content = Rc::new(RefCell::new(Content::default()));
+        let mut ctx = WatchContext::new();
+        ctx.add_watcher(&Rc::downgrade(&content));
+        ctx.update();
+        assert_eq!(content.borrow().downstream_runs, 1);
+        *content.borrow_mut().source = 2;
+        ctx.update();
+        assert!(!*content.borrow().parity);
+        assert_eq!(content.borrow().downstream_runs, 1);
+        *content.borrow_mut().source = 3;
+        ctx.update();
+        assert!(*content.borrow().parity);
+        assert_eq!(content.borrow().downstream_runs, 2);
+    }
+
+    #[test]
+    fn two_way_binding_settles() {
+        #[derive(Default)]
+        struct Content {
+            exact: Watched<f64>,
+            rounded: Watched<i32>,
+        }
+
+        impl Watcher<'static> for Content {
+            fn init(mut init: impl WatcherInit<'static, Self>) {
+                init.bind_two_way(
+                    |root| &mut root.exact,
+                    |root| &mut root.rounded,
+                    |exact| exact.round() as i32,
+                    // converting back and forth doesn't round trip
+                    |rounded| f64::from(*rounded) + 0.25,
+                );
+            }
+        }
+        let content = Rc::new(RefCell::new(Content::default()));
+        *content.borrow_mut().exact = 2.6;
+        let mut ctx = WatchContext::new();
+        // each change is synced in a single frame, without re-running the
+        // binding for the values it wrote itself
+        ctx.set_frame_limit(Some(1));
+        ctx.add_watcher(&Rc::downgrade(&content));
+        ctx.update();
+        assert_eq!(*content.borrow().exact, 2.6);
+        assert_eq!(*content.borrow().rounded, 3);
+        *content.borrow_mut().rounded = 5;
+        ctx.update();
+        assert_eq!(*content.borrow().exact, 5.25);
+        assert_eq!(*content.borrow().rounded, 5);
+        *content.borrow_mut().exact = 7.4;
+        ctx.update();
+        assert_eq!(*content.borrow().exact, 7.4);
+        assert_eq!(*content.borrow().rounded, 7);
+    }
+
+    #[test]
+    fn hash_change_detection() {
+        #[derive(Default, Hash)]
+        struct Shape {
+            points: std::vec::Vec<(i32, i32)>,
+            #[allow(dead_code)]
+            name: &'static str,
+        }
+
+        #[derive(Default)]
+        struct Content {
+            shape: Watched<Shape>,
+            runs: u32,
+        }
+
+        impl Watcher<'static> for Content {
+            fn init(mut init: impl WatcherInit<'static, Self>) {
+                init.watch(|root| {
+                    let _ = root.shape.points.len();
+                    root.runs += 1;
+                });
+            }
+        }
+        let content = Rc::new(RefCell::new(Content::default()));
+        let mut ctx = WatchContext::new();
+        ctx.add_watcher(&Rc::downgrade(&content));
+        ctx.update();
+        assert_eq!(content.borrow().runs, 1);
+        Watched::set_if_hash_neq(
+            &mut content.borrow_mut().shape,
+            Shape::default(),
+        );
+        Watched::modify_if_hash_changed(
+            &mut content.borrow_mut().shape,
+            |shape| shape.points.clear(),
+        );
+        ctx.update();
+        assert_eq!(content.borrow().runs, 1);
+        Watched::modify_if_fingerprint_changed(
+            &mut content.borrow_mut().shape,
+            |shape| shape.points.len(),
+            |shape| shape.name = "renamed",
+        );
+        ctx.update();
+        assert_eq!(content.borrow().runs, 1);
+        Watched::modify_if_hash_changed(
+            &mut content.borrow_mut().shape,
+            |shape| shape.points.push((1, 2)),
+        );
+        ctx.update();
+        assert_eq!(content.borrow().runs, 2);
+        Watched::set_if_hash_neq(
+            &mut content.borrow_mut().shape,
+            Shape::default(),
+        );
+        ctx.update();
+        assert_eq!(content.borrow().runs, 3);
+    }
+
+    #[test]
+    fn select_reruns_on_selected_change() {
+        #[derive(Default)]
+        struct Content {
+            source: WatchedCore<'static, (u32, u32)>,
+            first: u32,
+            runs: u32,
+        }
+
+        impl Watcher<'static> for Content {
+            fn init(mut init: impl WatcherInit<'static, Self>) {
+                init.watch_explicit(|arg, root| {
+                    root.first = root.source.select(arg, |pair| pair.0);
+                    root.runs += 1;
+                });
+            }
+        }
+        let content = Rc::new(RefCell::new(Content::default()));
+        let mut ctx = WatchContext::new();
+        ctx.add_watcher(&Rc::downgrade(&content));
+        content.borrow_mut().source.replace_external((0, 1));
+        content
+            .borrow_mut()
+            .source
+            .modify_external(|pair| pair.1 = 2);
+        ctx.update();
+        assert_eq!(content.borrow().runs, 1);
+        content.borrow_mut().source.set_if_neq_external((3, 2));
+        ctx.update();
+        assert_eq!(content.borrow().runs, 2);
+        assert_eq!(content.borrow().first, 3);
+        // changes made through a mutable reference can't be compared
+        content.borrow_mut().source.get_mut_external().1 = 4;
+        ctx.update();
+        assert_eq!(content.borrow().runs, 3);
+        assert_eq!(content.borrow().first, 3);
+    }
+
+    #[test]
+    fn select_alongside_whole_value() {
+        #[derive(Default)]
+        struct Content {
+            source: WatchedCore<'static, (u32, u32)>,
+            first_runs: u32,
+            whole_runs: u32,
+        }
+
+        impl Watcher<'static> for Content {
+            fn init(mut init: impl WatcherInit<'static, Self>) {
+                init.watch_explicit(|arg, root| {
+                    root.source.select(arg, |pair| pair.0);
+                    root.first_runs += 1;
+                });
+                init.watch_explicit(|arg, root| {
+                    root.source.get(arg);
+                    root.whole_runs += 1;
+                });
+            }
+        }
+        let content = Rc::new(RefCell::new(Content::default()));
+        let mut ctx = WatchContext::new();
+        ctx.add_watcher(&Rc::downgrade(&content));
+        for second in 1..=3 {
+            content.borrow_mut().source.set_if_neq_external((0, second));
+            ctx.update();
+        }
+        assert_eq!(content.borrow().first_runs, 1);
+        assert_eq!(content.borrow().whole_runs, 4);
+        content.borrow_mut().source.set_if_neq_external((1, 3));
+        ctx.update();
+        assert_eq!(content.borrow().first_runs, 2);
+        assert_eq!(content.borrow().whole_runs, 5);
+    }
+
+    #[test]
+    fn on_change_old_and_new() {
+        #[derive(Default)]
+        struct Content {
+            source: Watched<u32>,
+            core: WatchedCore<'static, u32>,
+            auto: std::vec::Vec<(u32, u32)>,
+            explicit: std::vec::Vec<(u32, u32)>,
+        }
+
+        impl Watcher<'static> for Content {
+            fn init(mut init: impl WatcherInit<'static, Self>) {
+                init.on_change(
+                    |root| &*root.source,
+                    |root, old, new| root.auto.push((*old, *new)),
+                );
+                init.on_change_explicit(
+                    |arg, root| root.core.get(arg),
+                    |root, old, new| root.explicit.push((*old, *new)),
+                );
+            }
+        }
+        let content = Rc::new(RefCell::new(Content::default()));
+        let mut ctx = WatchContext::new();
+        ctx.add_watcher(&Rc::downgrade(&content));
+        *content.borrow_mut().source = 1;
+        *content.borrow_mut().core.get_mut_external() = 2;
+        ctx.update();
+        // triggered, but not actually changed
+        *content.borrow_mut().source = 1;
+        ctx.update();
+        *content.borrow_mut().source = 3;
+        ctx.update();
+        assert_eq!(content.borrow().auto, [(0, 1), (1, 3)]);
+        assert_eq!(content.borrow().explicit, [(0, 2)]);
+    }
+
+    #[cfg(all(feature = "linux", target_os = "linux"))]
+    #[test]
+    fn sync_fd_readable() {
+        use std::os::fd::AsRawFd;
+
+        fn readable(fd: i32) -> bool {
+            let mut pollfd = libc::pollfd {
+                fd,
+                events: libc::POLLIN,
+                revents: 0,
+            };
+            unsafe { libc::poll(&mut pollfd, 1, 0) == 1 }
+        }
+
+        let meta = SyncWatchedMeta::new();
+        let mut ctx = WatchContext::from_owner(());
+        ctx.add_watch_raw("watch", |mut raw_arg| {
+            let (_, arg) = raw_arg.as_owner_and_arg();
+            meta.watched(arg);
+        });
+        let fd = ctx.sync_fd().unwrap().as_raw_fd();
+        assert!(!readable(fd));
+        let trigger = meta.create_trigger();
+        std::thread::spawn(move || trigger.trigger())
+            .join()
+            .unwrap();
+        assert!(readable(fd));
+        ctx.update();
+        assert!(!readable(fd));
+        ctx.remote().post(|_| ());
+        assert!(readable(fd));
+        ctx.update();
+        assert!(!readable(fd));
+    }
+
     #[test]
     fn send_received_by_watch() {
         use std::sync::mpsc::{channel, Receiver};
@@ -229,4 +1017,103 @@ mod tests {
         ctx.update();
         assert_eq!(content.borrow().dest, Some(4812));
     }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn serde_round_trip() {
+        use serde::Deserialize;
+
+        struct Content {
+            dest: i32,
+            source: Watched<i32>,
+        }
+
+        impl Watcher<'static> for Content {
+            fn init(mut init: impl WatcherInit<'static, Self>) {
+                init.watch(|root| {
+                    root.dest = *root.source;
+                });
+            }
+        }
+        let content = Rc::new(RefCell::new(Content {
+            dest: 0,
+            source: Watched::new(37),
+        }));
+        let mut ctx = WatchContext::new();
+        ctx.add_watcher(&Rc::downgrade(&content));
+        let json = serde_json::to_string(&content.borrow().source).unwrap();
+        assert_eq!(json, "37");
+        let mut de = serde_json::Deserializer::from_str("43");
+        Deserialize::deserialize_in_place(
+            &mut de,
+            &mut content.borrow_mut().source,
+        )
+        .unwrap();
+        ctx.update();
+        assert_eq!(content.borrow().dest, 43);
+
+        let mut queue = WatchedQueue::<'static, i32>::new();
+        queue.push_external(1);
+        queue.push_external(2);
+        let json = serde_json::to_string(&queue).unwrap();
+        assert_eq!(json, "[1,2]");
+        let queue: WatchedQueue<'static, i32> =
+            serde_json::from_str(&json).unwrap();
+        assert_eq!(serde_json::to_string(&queue).unwrap(), "[1,2]");
+        let cell: WatchedCell<u8> = serde_json::from_str("5").unwrap();
+        assert_eq!(serde_json::to_string(&cell).unwrap(), "5");
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn serde_in_place_only_triggers() {
+        use serde::Deserialize;
+
+        #[derive(Default)]
+        struct Content {
+            loaded: Watched<std::vec::Vec<i32>>,
+            load_runs: u32,
+            seen: usize,
+            seen_runs: u32,
+        }
+
+        impl Watcher<'static> for Content {
+            fn init(mut init: impl WatcherInit<'static, Self>) {
+                init.watch(|root| {
+                    root.load_runs += 1;
+                    let mut de = serde_json::Deserializer::from_str("[1, 2]");
+                    Deserialize::deserialize_in_place(
+                        &mut de,
+                        &mut root.loaded,
+                    )
+                    .unwrap();
+                });
+                init.watch(|root| {
+                    root.seen = root.loaded.len();
+                    root.seen_runs += 1;
+                });
+            }
+        }
+        let content = Rc::new(RefCell::new(Content::default()));
+        let mut ctx = WatchContext::new();
+        ctx.add_watcher(&Rc::downgrade(&content));
+        ctx.update();
+        assert_eq!(content.borrow().seen, 2);
+        // deserializing inside a watch doesn't bind that watch
+        content.borrow_mut().loaded.push(3);
+        ctx.update();
+        assert_eq!(content.borrow().load_runs, 1);
+        assert_eq!(content.borrow().seen, 3);
+        let seen_runs = content.borrow().seen_runs;
+        // a failed deserialize leaves the value alone, unnoticed
+        let mut de = serde_json::Deserializer::from_str("[4, \"five\"]");
+        assert!(Deserialize::deserialize_in_place(
+            &mut de,
+            &mut content.borrow_mut().loaded,
+        )
+        .is_err());
+        ctx.update();
+        assert_eq!(*content.borrow().loaded, [1, 2, 3]);
+        assert_eq!(content.borrow().seen_runs, seen_runs);
+    }
 }
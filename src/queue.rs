@@ -78,3 +78,61 @@ impl<T> WatchedQueue<'static, T, DefaultOwner> {
         self.current_meta.trigger_auto();
     }
 }
+
+#[cfg(feature = "serde")]
+mod serde_impls {
+    use {
+        alloc::collections::VecDeque,
+        core::cell::Cell,
+        serde::{Deserialize, Deserializer, Serialize, Serializer},
+    };
+
+    use super::WatchedQueue;
+
+    /// Serializes the items which have not been handled yet, as a sequence.
+    impl<'ctx, T, O> Serialize for WatchedQueue<'ctx, T, O>
+    where
+        T: Serialize,
+        O: ?Sized,
+    {
+        fn serialize<S: Serializer>(
+            &self,
+            serializer: S,
+        ) -> Result<S::Ok, S::Error> {
+            let queue = self.queue.take();
+            let result = serializer.collect_seq(&queue);
+            self.queue.set(queue);
+            result
+        }
+    }
+
+    /// Deserializing in place replaces the items which have not been
+    /// handled yet, and triggers any watch closures handling them.
+    impl<'de, 'ctx, T, O> Deserialize<'de> for WatchedQueue<'ctx, T, O>
+    where
+        T: Deserialize<'de>,
+        O: ?Sized,
+    {
+        fn deserialize<D: Deserializer<'de>>(
+            deserializer: D,
+        ) -> Result<Self, D::Error> {
+            let queue = VecDeque::deserialize(deserializer)?;
+            Ok(Self {
+                queue: Cell::new(queue),
+                ..Self::new()
+            })
+        }
+
+        fn deserialize_in_place<D: Deserializer<'de>>(
+            deserializer: D,
+            place: &mut Self,
+        ) -> Result<(), D::Error> {
+            VecDeque::deserialize_in_place(
+                deserializer,
+                place.queue.get_mut(),
+            )?;
+            place.current_meta.trigger_external();
+            Ok(())
+        }
+    }
+}
@@ -0,0 +1,354 @@
+/* SPDX-License-Identifier: (Apache-2.0 OR MIT OR Zlib) */
+/* Copyright © 2026 Violet Leonard */
+
+use std::{
+    boxed::Box,
+    fmt,
+    sync::{
+        atomic::{AtomicBool, AtomicUsize, Ordering},
+        Arc, Mutex, MutexGuard, PoisonError,
+    },
+    thread,
+};
+
+use crate::{
+    DefaultOwner, SyncTrigger, SyncWatchedGuard, SyncWatchedMeta, WatchArg,
+};
+
+/// The state of a [`WatchedResource`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum ResourceState<T, E> {
+    /// No load has finished yet.
+    Loading,
+    /// The latest load to finish succeeded.
+    Ready(T),
+    /// The latest load to finish failed.
+    Failed(E),
+}
+
+impl<T, E> ResourceState<T, E> {
+    /// Returns true if the state is `Loading`.
+    pub fn is_loading(&self) -> bool {
+        matches!(self, Self::Loading)
+    }
+
+    /// Get the loaded value, if the state is `Ready`.
+    pub fn ready(&self) -> Option<&T> {
+        match self {
+            Self::Ready(value) => Some(value),
+            _ => None,
+        }
+    }
+
+    /// Get the error, if the state is `Failed`.
+    pub fn failed(&self) -> Option<&E> {
+        match self {
+            Self::Failed(err) => Some(err),
+            _ => None,
+        }
+    }
+}
+
+impl<T, E> From<Result<T, E>> for ResourceState<T, E> {
+    fn from(result: Result<T, E>) -> Self {
+        match result {
+            Ok(value) => Self::Ready(value),
+            Err(err) => Self::Failed(err),
+        }
+    }
+}
+
+/// A job given to the spawner of a [`WatchedResource`].
+pub type ResourceJob = Box<dyn FnOnce() + Send>;
+
+struct Shared<T, E> {
+    version: AtomicUsize,
+    fetching: AtomicBool,
+    state: Mutex<ResourceState<T, E>>,
+}
+
+impl<T, E> Shared<T, E> {
+    fn lock(&self) -> MutexGuard<'_, ResourceState<T, E>> {
+        self.state.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+/// A value which is loaded by background work.
+///
+/// A resource is loaded by a source closure set up with
+/// [`WatcherInit::load_resource`](crate::WatcherInit::load_resource).  The
+/// source is run like a watch function, returning a loader which is handed
+/// to a spawner, by default running it on a new thread.  When the loader
+/// finishes, the resource becomes `Ready` or `Failed`, and watch functions
+/// which read it are re-run on the next update of their context.  Whenever
+/// the watched values the source referenced change, it is re-run, and the
+/// result of any earlier load which has not finished yet is discarded.
+/// Until the new load finishes, the result of the previous one remains,
+/// and [`is_fetching`](Self::is_fetching) returns true.
+///
+/// As with other values which are changed from other threads, the resource
+/// must be read by a watch function for its context to be notified.
+///
+/// # Examples
+/// ```rust
+///# use std::{rc::Rc, cell::RefCell};
+///# use drying_paint::*;
+/// struct Profile {
+///     user_id: Watched<u32>,
+///     name: WatchedResource<String, String>,
+///     title: String,
+/// }
+///
+/// impl Watcher<'static> for Profile {
+///     fn init(mut init: impl WatcherInit<'static, Self>) {
+///         init.load_resource(
+///             |root| &mut root.name,
+///             |root| {
+///                 let user_id = *root.user_id;
+///                 move || match user_id {
+///                     1 => Ok("Violet".to_string()),
+///                     _ => Err(format!("no user {}", user_id)),
+///                 }
+///             },
+///         );
+///         init.watch(|root| {
+///             root.title = match &*root.name.get_auto() {
+///                 ResourceState::Loading => "...".to_string(),
+///                 ResourceState::Ready(name) => name.clone(),
+///                 ResourceState::Failed(err) => err.clone(),
+///             };
+///         });
+///     }
+/// }
+///
+/// let profile = Rc::new(RefCell::new(Profile {
+///     user_id: Watched::new(1),
+///     name: WatchedResource::new(),
+///     title: String::new(),
+/// }));
+/// let mut ctx = WatchContext::new();
+/// ctx.add_watcher(&Rc::downgrade(&profile));
+/// ctx.run_until(|_| profile.borrow().title != "...");
+/// assert_eq!(profile.borrow().title, "Violet");
+/// *profile.borrow_mut().user_id = 2;
+/// ctx.run_until(|_| profile.borrow().title != "Violet");
+/// assert_eq!(profile.borrow().title, "no user 2");
+/// ```
+pub struct WatchedResource<T, E> {
+    meta: SyncWatchedMeta,
+    fetching_meta: SyncWatchedMeta,
+    shared: Arc<Shared<T, E>>,
+    spawner: Box<dyn Fn(ResourceJob)>,
+}
+
+impl<T, E> fmt::Debug for WatchedResource<T, E> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "(WatchedResource)")
+    }
+}
+
+impl<T, E> Default for WatchedResource<T, E> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T, E> WatchedResource<T, E> {
+    /// Create a new resource, in the `Loading` state, which runs loaders on
+    /// new threads.
+    pub fn new() -> Self {
+        Self::with_spawner(|job| {
+            thread::spawn(job);
+        })
+    }
+
+    /// Create a new resource, in the `Loading` state, which runs loaders
+    /// using the given spawner, e.g. to submit them to a thread pool.
+    pub fn with_spawner<S>(spawner: S) -> Self
+    where
+        S: 'static + Fn(ResourceJob),
+    {
+        Self {
+            meta: SyncWatchedMeta::new(),
+            fetching_meta: SyncWatchedMeta::new(),
+            shared: Arc::new(Shared {
+                version: AtomicUsize::new(0),
+                fetching: AtomicBool::new(false),
+                state: Mutex::new(ResourceState::Loading),
+            }),
+            spawner: Box::new(spawner),
+        }
+    }
+
+    /// Get the current state, binding a watch closure.
+    pub fn get<O: ?Sized>(
+        &self,
+        ctx: WatchArg<'_, '_, O>,
+    ) -> SyncWatchedGuard<'_, ResourceState<T, E>> {
+        self.meta.watched(ctx);
+        self.get_unwatched()
+    }
+
+    /// Get the current state, without binding any watch closure.
+    pub fn get_unwatched(&self) -> SyncWatchedGuard<'_, ResourceState<T, E>> {
+        SyncWatchedGuard {
+            guard: self.shared.lock(),
+        }
+    }
+
+    pub fn get_auto(&self) -> SyncWatchedGuard<'_, ResourceState<T, E>> {
+        WatchArg::<'_, 'static, DefaultOwner>::try_with_current(|arg| {
+            self.meta.watched(arg);
+        });
+        self.get_unwatched()
+    }
+
+    /// Returns true if a load has been started which has not finished yet,
+    /// binding a watch closure.
+    pub fn is_fetching<O: ?Sized>(&self, ctx: WatchArg<'_, '_, O>) -> bool {
+        self.fetching_meta.watched(ctx);
+        self.is_fetching_unwatched()
+    }
+
+    /// Returns true if a load has been started which has not finished yet,
+    /// without binding any watch closure.
+    pub fn is_fetching_unwatched(&self) -> bool {
+        self.shared.fetching.load(Ordering::Acquire)
+    }
+
+    pub fn is_fetching_auto(&self) -> bool {
+        WatchArg::<'_, 'static, DefaultOwner>::try_with_current(|arg| {
+            self.fetching_meta.watched(arg);
+        });
+        self.is_fetching_unwatched()
+    }
+}
+
+impl<T, E> WatchedResource<T, E>
+where
+    T: 'static + Send,
+    E: 'static + Send,
+{
+    /// Start loading a new value, superseding any load which is still in
+    /// progress.  Watch functions reading the state are only notified once
+    /// it finishes.
+    pub(crate) fn fetch<F, O>(&self, loader: F, ctx: WatchArg<'_, '_, O>)
+    where
+        F: 'static + Send + FnOnce() -> Result<T, E>,
+        O: ?Sized,
+    {
+        // the version is changed while holding the lock, so a superseded
+        // load can't finish between being checked and storing its result
+        let state = self.shared.lock();
+        let version = self.shared.version.fetch_add(1, Ordering::AcqRel) + 1;
+        let was_fetching = self.shared.fetching.swap(true, Ordering::AcqRel);
+        drop(state);
+        if !was_fetching {
+            self.fetching_meta.trigger_from(ctx);
+        }
+        let shared = Arc::clone(&self.shared);
+        let trigger: SyncTrigger = self.meta.create_trigger();
+        let fetching_trigger = self.fetching_meta.create_trigger();
+        (self.spawner)(Box::new(move || {
+            let result = loader();
+            let mut state = shared.lock();
+            if shared.version.load(Ordering::Acquire) == version {
+                *state = result.into();
+                shared.fetching.store(false, Ordering::Release);
+                drop(state);
+                trigger.trigger();
+                fetching_trigger.trigger();
+            }
+        }));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        cell::RefCell,
+        rc::Rc,
+        sync::mpsc::{channel, Receiver, Sender},
+    };
+
+    use crate::*;
+
+    struct Content {
+        query: Watched<u32>,
+        result: WatchedResource<u32, ()>,
+        reads: u32,
+        fetching: bool,
+    }
+
+    impl Watcher<'static> for Content {
+        fn init(mut init: impl WatcherInit<'static, Self>) {
+            init.load_resource(
+                |root| &mut root.result,
+                |root| {
+                    let query = *root.query;
+                    move || query.checked_sub(1).ok_or(())
+                },
+            );
+            init.watch(|root| {
+                let _ = root.result.get_auto();
+                root.reads += 1;
+            });
+            init.watch(|root| {
+                root.fetching = root.result.is_fetching_auto();
+            });
+        }
+    }
+
+    #[test]
+    fn superseded_load_discarded() {
+        // run the jobs by hand, in the reverse order they were spawned
+        let (send, recv): (Sender<ResourceJob>, Receiver<ResourceJob>) =
+            channel();
+        let content = Rc::new(RefCell::new(Content {
+            query: Watched::new(0),
+            result: WatchedResource::with_spawner(move |job| {
+                send.send(job).unwrap();
+            }),
+            reads: 0,
+            fetching: false,
+        }));
+        let mut ctx = WatchContext::new();
+        ctx.add_watcher(&Rc::downgrade(&content));
+        *content.borrow_mut().query = 5;
+        ctx.update();
+        let first = recv.try_recv().unwrap();
+        let second = recv.try_recv().unwrap();
+        assert!(recv.try_recv().is_err());
+        assert!(content.borrow().result.get_unwatched().is_loading());
+        assert!(content.borrow().fetching);
+        assert_eq!(content.borrow().reads, 1);
+        second();
+        first();
+        ctx.update();
+        assert_eq!(
+            *content.borrow().result.get_unwatched(),
+            ResourceState::Ready(4),
+        );
+        assert!(!content.borrow().fetching);
+        assert_eq!(content.borrow().reads, 2);
+        // a result arriving doesn't start another load
+        ctx.update();
+        assert!(recv.try_recv().is_err());
+        *content.borrow_mut().query = 0;
+        ctx.update();
+        // the previous result remains until the new load finishes
+        assert_eq!(
+            *content.borrow().result.get_unwatched(),
+            ResourceState::Ready(4),
+        );
+        assert!(content.borrow().fetching);
+        recv.try_recv().unwrap()();
+        ctx.update();
+        assert_eq!(
+            *content.borrow().result.get_unwatched(),
+            ResourceState::Failed(()),
+        );
+        assert!(!content.borrow().fetching);
+        assert_eq!(content.borrow().reads, 3);
+    }
+}
@@ -0,0 +1,196 @@
+/* SPDX-License-Identifier: (Apache-2.0 OR MIT OR Zlib) */
+/* Copyright © 2026 Violet Leonard */
+
+use alloc::{boxed::Box, vec::Vec};
+
+use crate::{WatchArg, WatchContext, WatchName, Watcher, WatcherHolder};
+
+type OwnerFn<O> = Box<dyn Send + FnOnce() -> O>;
+type SetupFn<O> = Box<dyn Send + FnOnce(&mut WatchContext<'static, O>)>;
+
+/// A description of a [`WatchContext`], which can be put together on one
+/// thread and built on another.
+///
+/// The watch system uses non-atomic reference counting internally, so
+/// neither [`WatchContext`] nor the watched values it tracks are `Send`.
+/// A `SendWatchContextBuilder` does not hold any of them: it holds a
+/// function which creates the owner, and the watch functions and watchers
+/// to add, all of which must be `Send`.  Once it has been moved to the
+/// thread which should run it, [`build`](Self::build) creates the owner
+/// and the [`WatchContext`], calling [`Watcher::init`] for each watcher on
+/// that thread.
+///
+/// Since the owner is created on the thread which runs the context, it
+/// may hold any watched values, such as
+/// [`WatchedCore`](crate::WatchedCore), and watchers inside it can be
+/// reached with an [`OwnerPath`](crate::OwnerPath).  Values shared with
+/// other threads must still be thread-safe, such as the
+/// [`SyncWatchedAtomicI32`](crate::SyncWatchedAtomicI32) family or
+/// [`SyncWatched`](crate::SyncWatched).
+///
+/// # Examples
+/// ```rust
+///# use drying_paint::*;
+/// struct State {
+///     source: WatchedCore<'static, i32, State>,
+///     dest: i32,
+/// }
+///
+/// let mut builder = SendWatchContextBuilder::new(|| State {
+///     source: WatchedCore::new(1),
+///     dest: 0,
+/// });
+/// builder.add_watch(|owner, arg| {
+///     owner.dest = *owner.source.get(arg);
+/// });
+/// let handle = std::thread::spawn(move || {
+///     let mut ctx = builder.build();
+///     ctx.owner().source.set_if_neq_external(5);
+///     ctx.update();
+///     ctx.owner().dest
+/// });
+/// assert_eq!(handle.join().unwrap(), 5);
+/// ```
+pub struct SendWatchContextBuilder<O> {
+    make_owner: OwnerFn<O>,
+    setup: Vec<SetupFn<O>>,
+    frame_limit: Option<Option<usize>>,
+}
+
+impl<O: 'static> SendWatchContextBuilder<O> {
+    /// Create a new SendWatchContextBuilder, which will call `make_owner`
+    /// to create the owner when the context is built.
+    pub fn new<F>(make_owner: F) -> Self
+    where
+        F: 'static + Send + FnOnce() -> O,
+    {
+        Self {
+            make_owner: Box::new(make_owner),
+            setup: Vec::new(),
+            frame_limit: None,
+        }
+    }
+
+    /// Create a new SendWatchContextBuilder with the given owner.
+    pub fn from_owner(owner: O) -> Self
+    where
+        O: Send,
+    {
+        Self::new(move || owner)
+    }
+
+    /// Add a watch function, which will first run when the context is
+    /// built.  See [`WatchContext::add_watch`].
+    #[cfg_attr(do_cycle_debug, track_caller)]
+    pub fn add_watch<F>(&mut self, func: F)
+    where
+        F: 'static + Send + Fn(&mut O, WatchArg<'_, 'static, O>),
+    {
+        let debug_name = WatchName::from_caller();
+        self.setup.push(Box::new(move |ctx| {
+            ctx.add_watch_raw(debug_name, move |mut raw_arg| {
+                let (owner, arg) = raw_arg.as_owner_and_arg();
+                func(owner, arg);
+            });
+        }));
+    }
+
+    /// Add a watcher, which will be initialized when the context is built.
+    /// See [`WatchContext::add_watcher`].
+    pub fn add_watcher<T>(&mut self, holder: &T)
+    where
+        T: 'static + Send + WatcherHolder<'static, O>,
+        T::Content: Watcher<'static, O>,
+    {
+        let holder = holder.clone();
+        self.setup
+            .push(Box::new(move |ctx| ctx.add_watcher(&holder)));
+    }
+
+    /// See [`WatchContext::set_frame_limit`].
+    pub fn set_frame_limit(&mut self, value: Option<usize>) {
+        self.frame_limit = Some(value);
+    }
+
+    /// Build the context on the current thread, adding the watch functions
+    /// and watchers in the order they were given.
+    pub fn build(self) -> WatchContext<'static, O> {
+        let mut ctx = WatchContext::from_owner((self.make_owner)());
+        if let Some(frame_limit) = self.frame_limit {
+            ctx.set_frame_limit(frame_limit);
+        }
+        for setup in self.setup {
+            setup(&mut ctx);
+        }
+        ctx
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::{Arc, Mutex};
+
+    use crate::*;
+
+    #[derive(Default)]
+    struct Child {
+        source: WatchedCore<'static, u32, Owner>,
+        dest: u32,
+    }
+
+    impl Watcher<'static, Owner> for Child {
+        fn init(mut init: impl WatcherInit<'static, Self, Owner>) {
+            init.watch_explicit(|arg, root| {
+                root.dest = *root.source.get(arg) * 2;
+            });
+        }
+    }
+
+    #[derive(Default)]
+    struct Shared {
+        source: SyncWatchedAtomicU32,
+        dest: u32,
+    }
+
+    impl Watcher<'static, Owner> for Shared {
+        fn init(mut init: impl WatcherInit<'static, Self, Owner>) {
+            init.watch_explicit(|arg, root| {
+                root.dest = root.source.get(arg) * 2;
+            });
+        }
+    }
+
+    #[derive(Default)]
+    struct Owner {
+        child: Child,
+    }
+
+    #[test]
+    fn watchers_built_on_thread() {
+        let shared = Arc::new(Mutex::new(Shared::default()));
+        let mut builder = SendWatchContextBuilder::new(|| Owner {
+            child: Child {
+                source: WatchedCore::new(21),
+                dest: 0,
+            },
+        });
+        let path = OwnerPath::new(|owner: &mut Owner| &mut owner.child);
+        builder.add_watcher(&path);
+        builder.add_watcher(&Arc::downgrade(&shared));
+        shared.lock().unwrap().source.store(3);
+        let worker_shared = Arc::clone(&shared);
+        std::thread::spawn(move || {
+            let mut ctx = builder.build();
+            ctx.update();
+            assert_eq!(ctx.owner().child.dest, 42);
+            assert_eq!(worker_shared.lock().unwrap().dest, 6);
+            ctx.owner().child.source.set_if_neq_external(4);
+            worker_shared.lock().unwrap().source.store(5);
+            ctx.update();
+            assert_eq!(ctx.owner().child.dest, 8);
+        })
+        .join()
+        .unwrap();
+        assert_eq!(shared.lock().unwrap().dest, 10);
+    }
+}
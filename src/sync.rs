@@ -2,22 +2,33 @@
 /* Copyright © 2021 Violet Leonard */
 
 use {
-    alloc::sync::{Arc, Weak},
+    alloc::{
+        boxed::Box,
+        sync::{Arc, Weak},
+        vec::Vec,
+    },
     core::{
-        cell::Cell,
+        cell::{Cell, UnsafeCell},
         fmt, mem, ptr,
         sync::atomic::{AtomicPtr, AtomicUsize, Ordering},
+        task::Waker,
     },
 };
 
 use crate::{trigger::WatchArg, WatchedMeta};
 
+#[cfg(feature = "std")]
+use std::time::{Duration, Instant};
+
 const FLAG_COUNT: usize = usize::BITS as usize;
 
 pub(crate) struct SyncContext<'ctx, O: ?Sized> {
-    flag: Arc<AtomicUsize>,
+    flag: Arc<SyncFlag>,
     watched: [WatchedMeta<'ctx, O>; FLAG_COUNT],
     next_index: Cell<usize>,
+    remote: Arc<RemoteQueue<O>>,
+    #[cfg(feature = "std")]
+    alarm: Cell<Option<Instant>>,
 }
 
 impl<'ctx, O: ?Sized> SyncContext<'ctx, O> {
@@ -26,26 +37,342 @@ impl<'ctx, O: ?Sized> SyncContext<'ctx, O> {
             flag: Arc::default(),
             watched: [0; FLAG_COUNT].map(|_| WatchedMeta::new()),
             next_index: Cell::new(0),
+            remote: Arc::new(RemoteQueue::new()),
+            #[cfg(feature = "std")]
+            alarm: Cell::new(None),
         }
     }
 
     pub fn check_for_updates(&self) {
-        let set_bits = self.flag.swap(0, Ordering::Acquire);
+        // reset first, so that anything which fires after this point will
+        // signal it again
+        #[cfg(all(feature = "linux", target_os = "linux"))]
+        self.flag.event_fd.reset();
+        let set_bits = self.flag.bits.swap(0, Ordering::Acquire);
         for i in 0..FLAG_COUNT {
             if (set_bits & (1 << i)) != 0 {
                 self.watched[i].trigger_external();
             }
         }
     }
+
+    /// Returns true if any trigger has fired or any function has been
+    /// posted since the last call to `check_for_updates`.
+    pub fn has_pending(&self) -> bool {
+        self.flag.bits.load(Ordering::Acquire) != 0
+            || !self.remote.head.load(Ordering::Acquire).is_null()
+    }
+
+    /// Register a waker to be woken the next time a trigger fires or a
+    /// function is posted.  Only the most recently registered waker is
+    /// kept.
+    pub fn register_waker(&self, waker: &Waker) {
+        self.flag.waker.register(waker);
+    }
+
+    /// Wake the registered waker from another thread once `timeout` has
+    /// passed, unless it is already due to be woken by then.
+    #[cfg(feature = "std")]
+    pub fn wake_after(&self, timeout: Duration) {
+        let now = Instant::now();
+        let at = match now.checked_add(timeout) {
+            Some(at) => at,
+            None => return,
+        };
+        if let Some(alarm) = self.alarm.get() {
+            if now < alarm && alarm <= at {
+                return;
+            }
+        }
+        self.alarm.set(Some(at));
+        let flag = Arc::downgrade(&self.flag);
+        std::thread::spawn(move || {
+            std::thread::sleep(timeout);
+            if let Some(flag) = flag.upgrade() {
+                flag.waker.wake();
+            }
+        });
+    }
+
+    /// Remove the registered waker, returning it if it has not been woken.
+    #[cfg(feature = "std")]
+    pub fn take_waker(&self) -> Option<Waker> {
+        self.flag.waker.take()
+    }
+
+    /// Get the eventfd which becomes readable whenever this context has
+    /// sync work pending.
+    #[cfg(all(feature = "linux", target_os = "linux"))]
+    pub fn sync_fd(&self) -> std::io::Result<std::os::fd::BorrowedFd<'_>> {
+        let (fd, created) = self.flag.event_fd.get_or_create()?;
+        if created && self.has_pending() {
+            // work which arrived before the fd existed
+            self.flag.event_fd.signal();
+        }
+        Ok(fd)
+    }
+
+    pub fn create_handle(&self) -> ContextHandle<O> {
+        ContextHandle {
+            remote: Arc::downgrade(&self.remote),
+            flag: Arc::downgrade(&self.flag),
+        }
+    }
+
+    pub fn take_posted(&self) -> Vec<Box<RemoteFn<O>>> {
+        self.remote.take_all()
+    }
+}
+
+/// The state shared between a context and the triggers bound to it.
+#[derive(Default)]
+struct SyncFlag {
+    bits: AtomicUsize,
+    waker: AtomicWaker,
+    #[cfg(all(feature = "linux", target_os = "linux"))]
+    event_fd: crate::event_fd::EventFd,
+}
+
+impl SyncFlag {
+    fn set(&self, mask: usize) {
+        self.bits.fetch_or(mask, Ordering::Release);
+        self.notify();
+    }
+
+    fn notify(&self) {
+        #[cfg(all(feature = "linux", target_os = "linux"))]
+        self.event_fd.signal();
+        self.waker.wake();
+    }
+}
+
+const WAKER_IDLE: usize = 0;
+const WAKER_REGISTERING: usize = 0b01;
+const WAKER_WAKING: usize = 0b10;
+
+/// A slot for a single waker which may be woken from any thread, while
+/// being registered from the thread of the context.
+///
+/// `state` acts as a lock for `waker`: whichever of `register` or `wake`
+/// moves it away from `WAKER_IDLE` has exclusive access until it is reset.
+#[derive(Default)]
+struct AtomicWaker {
+    state: AtomicUsize,
+    waker: UnsafeCell<Option<Waker>>,
+}
+
+// SAFETY: access to the UnsafeCell is guarded by `state`
+unsafe impl Send for AtomicWaker {}
+unsafe impl Sync for AtomicWaker {}
+
+impl AtomicWaker {
+    fn register(&self, waker: &Waker) {
+        match self
+            .state
+            .compare_exchange(
+                WAKER_IDLE,
+                WAKER_REGISTERING,
+                Ordering::Acquire,
+                Ordering::Acquire,
+            )
+            .unwrap_or_else(|state| state)
+        {
+            WAKER_IDLE => {
+                // we hold the lock on `waker`
+                let slot = unsafe { &mut *self.waker.get() };
+                match slot {
+                    Some(old) if old.will_wake(waker) => (),
+                    _ => *slot = Some(waker.clone()),
+                }
+                if self
+                    .state
+                    .compare_exchange(
+                        WAKER_REGISTERING,
+                        WAKER_IDLE,
+                        Ordering::AcqRel,
+                        Ordering::Acquire,
+                    )
+                    .is_err()
+                {
+                    // a wake happened while registering, it is our
+                    // responsibility to deliver it
+                    let waker = slot.take();
+                    self.state.swap(WAKER_IDLE, Ordering::AcqRel);
+                    if let Some(waker) = waker {
+                        waker.wake();
+                    }
+                }
+            }
+            WAKER_WAKING => {
+                // currently being woken, so wake the new waker directly
+                waker.wake_by_ref();
+            }
+            _ => {
+                // registering concurrently is not supported, the context
+                // only registers from its own thread
+            }
+        }
+    }
+
+    /// Remove the registered waker, returning it if it was not woken.
+    #[cfg(feature = "std")]
+    fn take(&self) -> Option<Waker> {
+        if self
+            .state
+            .compare_exchange(
+                WAKER_IDLE,
+                WAKER_REGISTERING,
+                Ordering::Acquire,
+                Ordering::Acquire,
+            )
+            .is_err()
+        {
+            // currently being woken, which also takes the waker
+            return None;
+        }
+        // we hold the lock on `waker`
+        let waker = unsafe { (*self.waker.get()).take() };
+        if self.state.swap(WAKER_IDLE, Ordering::AcqRel) & WAKER_WAKING != 0 {
+            // a wake happened while taking, deliver it
+            if let Some(waker) = waker {
+                waker.wake();
+            }
+            return None;
+        }
+        waker
+    }
+
+    fn wake(&self) {
+        match self.state.fetch_or(WAKER_WAKING, Ordering::AcqRel) {
+            WAKER_IDLE => {
+                // we hold the lock on `waker`
+                let waker = unsafe { (*self.waker.get()).take() };
+                self.state.fetch_and(!WAKER_WAKING, Ordering::Release);
+                if let Some(waker) = waker {
+                    waker.wake();
+                }
+            }
+            _ => {
+                // either register will see the WAKING bit and wake, or
+                // another thread is already waking
+            }
+        }
+    }
+}
+
+pub(crate) type RemoteFn<O> = dyn FnOnce(&mut O) + Send;
+
+struct RemoteNode<O: ?Sized> {
+    func: Box<RemoteFn<O>>,
+    next: *mut RemoteNode<O>,
+}
+
+/// A lock-free stack of functions posted from other threads.  Items are
+/// reversed when taken so they run in the order they were posted.
+struct RemoteQueue<O: ?Sized> {
+    head: AtomicPtr<RemoteNode<O>>,
+}
+
+impl<O: ?Sized> Drop for RemoteQueue<O> {
+    fn drop(&mut self) {
+        drop(self.take_all());
+    }
+}
+
+impl<O: ?Sized> RemoteQueue<O> {
+    fn new() -> Self {
+        Self {
+            head: AtomicPtr::new(ptr::null_mut()),
+        }
+    }
+
+    fn push(&self, func: Box<RemoteFn<O>>) {
+        let node = Box::into_raw(Box::new(RemoteNode {
+            func,
+            next: ptr::null_mut(),
+        }));
+        let mut head = self.head.load(Ordering::Relaxed);
+        loop {
+            // the node is not shared until the exchange below succeeds
+            unsafe {
+                (*node).next = head;
+            }
+            match self.head.compare_exchange_weak(
+                head,
+                node,
+                Ordering::Release,
+                Ordering::Relaxed,
+            ) {
+                Ok(_) => break,
+                Err(current) => head = current,
+            }
+        }
+    }
+
+    fn take_all(&self) -> Vec<Box<RemoteFn<O>>> {
+        let mut node_ptr = self.head.swap(ptr::null_mut(), Ordering::Acquire);
+        let mut items = Vec::new();
+        while !node_ptr.is_null() {
+            // we took ownership of the whole list in the swap above
+            let node = unsafe { Box::from_raw(node_ptr) };
+            node_ptr = node.next;
+            items.push(node.func);
+        }
+        items.reverse();
+        items
+    }
+}
+
+/// A handle which may be sent to other threads to post functions which will
+/// be run on the thread of a [`WatchContext`](crate::WatchContext).
+///
+/// Posted functions are run at the start of the next call to
+/// [`WatchContext::update`](crate::WatchContext::update), and receive a
+/// mutable reference to the owner of the context.  Any watched values they
+/// modify will trigger watch functions as part of that same update.
+pub struct ContextHandle<O: ?Sized> {
+    remote: Weak<RemoteQueue<O>>,
+    flag: Weak<SyncFlag>,
+}
+
+impl<O: ?Sized> Clone for ContextHandle<O> {
+    fn clone(&self) -> Self {
+        Self {
+            remote: Weak::clone(&self.remote),
+            flag: Weak::clone(&self.flag),
+        }
+    }
+}
+
+impl<O: ?Sized> fmt::Debug for ContextHandle<O> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "(ContextHandle)")
+    }
+}
+
+impl<O: ?Sized> ContextHandle<O> {
+    /// Post a function to be run on the thread of the context.  If the
+    /// context has been dropped, the function is dropped without running.
+    pub fn post<F>(&self, func: F)
+    where
+        F: 'static + Send + FnOnce(&mut O),
+    {
+        if let Some(remote) = self.remote.upgrade() {
+            remote.push(Box::new(func));
+            if let Some(flag) = self.flag.upgrade() {
+                flag.notify();
+            }
+        }
+    }
 }
 
 struct FlagPole {
-    ptr: AtomicPtr<AtomicUsize>,
+    ptr: AtomicPtr<SyncFlag>,
 }
 
 impl Drop for FlagPole {
     fn drop(&mut self) {
-        let flag_ptr: *mut AtomicUsize = *self.ptr.get_mut();
+        let flag_ptr: *mut SyncFlag = *self.ptr.get_mut();
         if !flag_ptr.is_null() {
             // drop one weak reference
             unsafe {
@@ -64,10 +391,12 @@ impl Default for FlagPole {
 }
 
 impl FlagPole {
-    fn set(&self, value: Weak<AtomicUsize>) {
-        let flag_ptr = value.into_raw() as *mut AtomicUsize;
+    /// Store the flag, only if no flag was stored before.  Returns whether
+    /// the flag was stored.
+    fn set(&self, value: Weak<SyncFlag>) -> bool {
+        let flag_ptr = value.into_raw() as *mut SyncFlag;
         // Store the new value only if the current value is null
-        if self
+        let stored = self
             .ptr
             .compare_exchange(
                 ptr::null_mut(),
@@ -75,17 +404,22 @@ impl FlagPole {
                 Ordering::Release,
                 Ordering::Relaxed,
             )
-            .is_err()
-        {
+            .is_ok();
+        if !stored {
             // If the store failed, ensure the ref count is
             // properly decremented
             unsafe {
                 Weak::from_raw(flag_ptr);
             }
         }
+        stored
     }
 
-    fn get(&self) -> Weak<AtomicUsize> {
+    fn is(&self, flag: &Arc<SyncFlag>) -> bool {
+        ptr::eq(self.ptr.load(Ordering::Acquire), Arc::as_ptr(flag))
+    }
+
+    fn get(&self) -> Weak<SyncFlag> {
         let flag_ptr = self.ptr.load(Ordering::Acquire);
         if flag_ptr.is_null() {
             Weak::new()
@@ -105,6 +439,14 @@ struct SharedMeta {
     mask: AtomicUsize,
 }
 
+impl SharedMeta {
+    fn trigger(&self) {
+        if let Some(flag) = self.flag_pole.get().upgrade() {
+            flag.set(self.mask.load(Ordering::Relaxed));
+        }
+    }
+}
+
 /// SyncWatchedMeta is like WatchedMeta, however allows you to create
 /// a trigger which may be sent to other threads.
 ///
@@ -112,14 +454,14 @@ struct SharedMeta {
 /// context will be re-run.
 pub struct SyncWatchedMeta {
     data: Arc<SharedMeta>,
-    index: Cell<usize>,
+    index: AtomicUsize,
 }
 
 impl Default for SyncWatchedMeta {
     fn default() -> Self {
         Self {
             data: Arc::default(),
-            index: Cell::new(usize::MAX),
+            index: AtomicUsize::new(usize::MAX),
         }
     }
 }
@@ -139,21 +481,62 @@ impl SyncWatchedMeta {
     /// When run in a function designed to watch a value, will bind so that
     /// function will be re-run when a trigger associated with this
     /// AtomicWatchedMeta is invoked.
+    ///
+    /// A SyncWatchedMeta belongs to the first context it is watched in;
+    /// watch functions in any other context do not bind to it.
     pub fn watched<O: ?Sized>(&self, ctx: WatchArg<'_, '_, O>) {
         if let Some(sctx) = ctx.frame_info.sync_context.upgrade() {
-            if self.index.get() == usize::MAX {
-                let index = sctx.next_index.get();
-                sctx.next_index.set(index + 1 % FLAG_COUNT);
-                let mask = 1 << index;
-                let weak_flag = Arc::downgrade(&sctx.flag);
-                self.data.mask.store(mask, Ordering::Relaxed);
-                self.data.flag_pole.set(weak_flag);
-                self.index.set(index);
+            if let Some(index) = self.bound_index(&sctx) {
+                sctx.watched[index].watched(ctx);
             }
-            sctx.watched[self.index.get()].watched(ctx);
         }
     }
 
+    /// Get the flag index of this meta in the given context, binding it to
+    /// the context if it was not bound to any yet.
+    fn bound_index<O: ?Sized>(
+        &self,
+        sctx: &SyncContext<'_, O>,
+    ) -> Option<usize> {
+        if self.data.flag_pole.is(&sctx.flag) {
+            // only the thread running the context sets the index
+            return Some(self.index.load(Ordering::Relaxed));
+        }
+        if !self.data.flag_pole.set(Arc::downgrade(&sctx.flag)) {
+            // bound to another context
+            return None;
+        }
+        // a trigger which sees the flag before the mask does nothing, but
+        // it can only race with the first read of the value, which follows
+        let index = sctx.next_index.get();
+        sctx.next_index.set((index + 1) % FLAG_COUNT);
+        self.data.mask.store(1 << index, Ordering::Relaxed);
+        self.index.store(index, Ordering::Relaxed);
+        Some(index)
+    }
+
+    /// Invoke the trigger associated with this SyncWatchedMeta directly.
+    /// Unlike [`WatchedMeta::trigger`], this may be called from any thread.
+    pub fn trigger(&self) {
+        self.data.trigger();
+    }
+
+    /// Invoke the trigger from a watch function.  If this meta is bound to
+    /// the context running it, watch functions are notified straight away,
+    /// rather than on its next update.
+    #[cfg(feature = "std")]
+    #[cfg_attr(do_cycle_debug, track_caller)]
+    pub(crate) fn trigger_from<O: ?Sized>(&self, ctx: WatchArg<'_, '_, O>) {
+        if let Some(sctx) = ctx.frame_info.sync_context.upgrade() {
+            if self.data.flag_pole.is(&sctx.flag) {
+                let index = self.index.load(Ordering::Relaxed);
+                sctx.watched[index].trigger(ctx);
+                return;
+            }
+        }
+        self.trigger();
+    }
+
     /// Create a trigger for this AtomicWatchedMeta which may be sent to
     /// another thread.
     pub fn create_trigger(&self) -> SyncTrigger {
@@ -186,10 +569,7 @@ impl SyncTrigger {
 
     pub fn trigger(&self) {
         if let Some(data) = self.data.upgrade() {
-            if let Some(flag) = data.flag_pole.get().upgrade() {
-                let mask = data.mask.load(Ordering::Relaxed);
-                flag.fetch_or(mask, Ordering::Release);
-            }
+            data.trigger();
         }
     }
 }
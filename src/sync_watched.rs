@@ -0,0 +1,240 @@
+/* SPDX-License-Identifier: (Apache-2.0 OR MIT OR Zlib) */
+/* Copyright © 2026 Violet Leonard */
+
+use std::{
+    fmt,
+    ops::Deref,
+    sync::{Arc, Mutex, MutexGuard, PoisonError},
+};
+
+use crate::{DefaultOwner, SyncTrigger, SyncWatchedMeta, WatchArg};
+
+/// Create a watched value which may be set from any thread.
+///
+/// The setter may be cloned and sent to other threads.  Each time it is
+/// used, the newest value is stored and watch functions which read the
+/// [`SyncWatched`] half are re-run on the next update of their context.
+/// Unlike [`watched_channel`](crate::watched_channel), intermediate values
+/// are not queued, only the latest one is kept.
+///
+/// # Examples
+/// ```rust
+///# use std::{rc::Rc, cell::RefCell};
+///# use drying_paint::*;
+/// struct Progress {
+///     percent: SyncWatched<u8>,
+///     shown: u8,
+/// }
+///
+/// impl Watcher<'static> for Progress {
+///     fn init(mut init: impl WatcherInit<'static, Self>) {
+///         init.watch(|root| {
+///             root.shown = *root.percent.get_auto();
+///         });
+///     }
+/// }
+///
+/// let (setter, percent) = sync_watched(0);
+/// let progress = Rc::new(RefCell::new(Progress { percent, shown: 0 }));
+/// let mut ctx = WatchContext::new();
+/// ctx.add_watcher(&Rc::downgrade(&progress));
+/// std::thread::spawn(move || {
+///     for i in 0..=100 {
+///         setter.set(i);
+///     }
+/// })
+/// .join()
+/// .unwrap();
+/// ctx.update();
+/// assert_eq!(progress.borrow().shown, 100);
+/// ```
+pub fn sync_watched<T>(initial: T) -> (SyncWatchedSetter<T>, SyncWatched<T>) {
+    let reader = SyncWatched::new(initial);
+    (reader.setter(), reader)
+}
+
+fn lock<T: ?Sized>(mutex: &Mutex<T>) -> MutexGuard<'_, T> {
+    mutex.lock().unwrap_or_else(PoisonError::into_inner)
+}
+
+/// The half of a [`sync_watched`] pair which may be sent to other threads
+/// to update the value.
+pub struct SyncWatchedSetter<T: ?Sized> {
+    trigger: SyncTrigger,
+    shared: Arc<Mutex<T>>,
+}
+
+impl<T: ?Sized> Clone for SyncWatchedSetter<T> {
+    fn clone(&self) -> Self {
+        Self {
+            trigger: self.trigger.clone(),
+            shared: Arc::clone(&self.shared),
+        }
+    }
+}
+
+impl<T: ?Sized> fmt::Debug for SyncWatchedSetter<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "(SyncWatchedSetter)")
+    }
+}
+
+impl<T: ?Sized> SyncWatchedSetter<T> {
+    /// Modify the value in place, and notify watchers that the value has
+    /// changed.
+    pub fn modify<F, R>(&self, func: F) -> R
+    where
+        F: FnOnce(&mut T) -> R,
+    {
+        let ret = func(&mut lock(&self.shared));
+        self.trigger.trigger();
+        ret
+    }
+}
+
+impl<T> SyncWatchedSetter<T> {
+    /// Store a new value, and notify watchers that the value has changed.
+    pub fn set(&self, value: T) {
+        *lock(&self.shared) = value;
+        self.trigger.trigger();
+    }
+
+    /// Store a new value only if it is different from the current one,
+    /// notifying watchers only in that case.
+    pub fn set_if_neq(&self, value: T)
+    where
+        T: PartialEq,
+    {
+        let mut current = lock(&self.shared);
+        if *current != value {
+            *current = value;
+            drop(current);
+            self.trigger.trigger();
+        }
+    }
+}
+
+/// The half of a [`sync_watched`] pair which is read by watch functions.
+pub struct SyncWatched<T: ?Sized> {
+    meta: SyncWatchedMeta,
+    shared: Arc<Mutex<T>>,
+}
+
+impl<T: ?Sized> fmt::Debug for SyncWatched<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "(SyncWatched)")
+    }
+}
+
+impl<T: Default> Default for SyncWatched<T> {
+    fn default() -> Self {
+        Self::new(T::default())
+    }
+}
+
+impl<T> SyncWatched<T> {
+    /// Create a new SyncWatched.  Use [`SyncWatched::setter`] to create
+    /// handles which can change the value.
+    pub fn new(value: T) -> Self {
+        Self {
+            meta: SyncWatchedMeta::new(),
+            shared: Arc::new(Mutex::new(value)),
+        }
+    }
+}
+
+impl<T: ?Sized> SyncWatched<T> {
+    /// Create another setter for this value.
+    pub fn setter(&self) -> SyncWatchedSetter<T> {
+        SyncWatchedSetter {
+            trigger: self.meta.create_trigger(),
+            shared: Arc::clone(&self.shared),
+        }
+    }
+
+    /// Get the latest value, binding a watch closure.
+    ///
+    /// Setters will block while the returned guard is held.
+    pub fn get<O: ?Sized>(
+        &self,
+        ctx: WatchArg<'_, '_, O>,
+    ) -> SyncWatchedGuard<'_, T> {
+        self.meta.watched(ctx);
+        self.get_unwatched()
+    }
+
+    /// Get the latest value, without binding any watch closure.
+    pub fn get_unwatched(&self) -> SyncWatchedGuard<'_, T> {
+        SyncWatchedGuard {
+            guard: lock(&self.shared),
+        }
+    }
+
+    pub fn get_auto(&self) -> SyncWatchedGuard<'_, T> {
+        WatchArg::<'_, 'static, DefaultOwner>::try_with_current(|arg| {
+            self.meta.watched(arg);
+        });
+        self.get_unwatched()
+    }
+}
+
+/// A reference to the value of a [`SyncWatched`].
+pub struct SyncWatchedGuard<'a, T: ?Sized> {
+    pub(crate) guard: MutexGuard<'a, T>,
+}
+
+impl<'a, T: ?Sized> Deref for SyncWatchedGuard<'a, T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        &self.guard
+    }
+}
+
+impl<'a, T: fmt::Debug + ?Sized> fmt::Debug for SyncWatchedGuard<'a, T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Debug::fmt(&**self, f)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{cell::RefCell, rc::Rc};
+
+    use crate::*;
+
+    #[test]
+    fn only_changes_trigger() {
+        struct Content {
+            source: SyncWatched<&'static str>,
+            runs: u32,
+        }
+
+        impl Watcher<'static> for Content {
+            fn init(mut init: impl WatcherInit<'static, Self>) {
+                init.watch_explicit(|arg, root| {
+                    root.source.get(arg);
+                    root.runs += 1;
+                });
+            }
+        }
+
+        let (setter, source) = sync_watched("a");
+        let content = Rc::new(RefCell::new(Content { source, runs: 0 }));
+        let mut ctx = WatchContext::new();
+        ctx.add_watcher(&Rc::downgrade(&content));
+        assert_eq!(content.borrow().runs, 1);
+        let thread_setter = setter.clone();
+        std::thread::spawn(move || thread_setter.set_if_neq("a"))
+            .join()
+            .unwrap();
+        ctx.update();
+        assert_eq!(content.borrow().runs, 1);
+        std::thread::spawn(move || setter.set_if_neq("b"))
+            .join()
+            .unwrap();
+        ctx.update();
+        assert_eq!(content.borrow().runs, 2);
+        assert_eq!(*content.borrow().source.get_unwatched(), "b");
+    }
+}
@@ -0,0 +1,592 @@
+/* SPDX-License-Identifier: (Apache-2.0 OR MIT OR Zlib) */
+/* Copyright © 2026 Violet Leonard */
+
+use {
+    alloc::{
+        boxed::Box,
+        collections::BinaryHeap,
+        rc::{Rc, Weak},
+    },
+    core::{
+        cell::{Cell, RefCell},
+        cmp::{Ordering, Reverse},
+        convert::TryFrom,
+        fmt,
+        time::Duration,
+    },
+};
+
+use crate::{Clock, SyncTrigger, SyncWatchedMeta, WatchArg};
+
+#[cfg(feature = "std")]
+use crate::DefaultOwner;
+
+struct Deadline {
+    at: Duration,
+    trigger: SyncTrigger,
+    scheduled: Weak<Cell<Option<Duration>>>,
+}
+
+impl Deadline {
+    /// A deadline is stale once its timer has been restarted or dropped.
+    fn is_current(&self) -> bool {
+        self.scheduled
+            .upgrade()
+            .is_some_and(|scheduled| scheduled.get() == Some(self.at))
+    }
+}
+
+impl PartialEq for Deadline {
+    fn eq(&self, other: &Self) -> bool {
+        self.at == other.at
+    }
+}
+
+impl Eq for Deadline {}
+
+impl PartialOrd for Deadline {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Deadline {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.at.cmp(&other.at)
+    }
+}
+
+/// The clock of a context, along with the deadlines of the timers which
+/// have been read by its watch functions.
+pub(crate) struct TimerQueue {
+    now: Cell<Duration>,
+    clock: RefCell<Option<Box<dyn Clock>>>,
+    deadlines: RefCell<BinaryHeap<Reverse<Deadline>>>,
+}
+
+impl TimerQueue {
+    pub fn new() -> Self {
+        #[cfg(feature = "std")]
+        let clock: Option<Box<dyn Clock>> =
+            Some(Box::new(crate::StdClock::new()));
+        #[cfg(not(feature = "std"))]
+        let clock = None;
+        Self {
+            now: Cell::new(Duration::ZERO),
+            clock: RefCell::new(clock),
+            deadlines: RefCell::default(),
+        }
+    }
+
+    pub fn now(&self) -> Duration {
+        self.now.get()
+    }
+
+    fn read_clock(&self) -> Option<Duration> {
+        self.clock.borrow().as_ref().map(|clock| clock.now())
+    }
+
+    /// Read the clock, if there is one.
+    pub fn tick(&self) {
+        if let Some(now) = self.read_clock() {
+            self.set_now(now);
+        }
+    }
+
+    pub fn set_clock(&self, clock: Box<dyn Clock>) {
+        *self.clock.borrow_mut() = Some(clock);
+    }
+
+    /// Stop reading the clock, and set the time manually.
+    pub fn advance_to(&self, now: Duration) {
+        *self.clock.borrow_mut() = None;
+        self.set_now(now);
+    }
+
+    fn set_now(&self, now: Duration) {
+        // time never goes backwards
+        self.now.set(self.now.get().max(now));
+        let mut deadlines = self.deadlines.borrow_mut();
+        while let Some(Reverse(next)) = deadlines.peek() {
+            if next.at > self.now.get() {
+                break;
+            }
+            if let Some(Reverse(due)) = deadlines.pop() {
+                if due.is_current() {
+                    due.trigger.trigger();
+                }
+            }
+        }
+    }
+
+    fn schedule(
+        &self,
+        at: Duration,
+        trigger: SyncTrigger,
+        scheduled: Weak<Cell<Option<Duration>>>,
+    ) {
+        self.deadlines.borrow_mut().push(Reverse(Deadline {
+            at,
+            trigger,
+            scheduled,
+        }));
+    }
+
+    pub fn next_deadline(&self) -> Option<Duration> {
+        let mut deadlines = self.deadlines.borrow_mut();
+        while let Some(Reverse(next)) = deadlines.peek() {
+            if next.is_current() {
+                return Some(next.at);
+            }
+            deadlines.pop();
+        }
+        None
+    }
+
+    /// How long until the next deadline, according to the clock.
+    pub fn clock_timeout(&self) -> Option<Duration> {
+        let next = self.next_deadline()?;
+        Some(next.saturating_sub(self.read_clock()?))
+    }
+
+    /// How long a thread may sleep until the next deadline, if the clock
+    /// keeps pace with the system's.
+    #[cfg(feature = "std")]
+    pub fn sleep_timeout(&self) -> Option<Duration> {
+        let real_time = self
+            .clock
+            .borrow()
+            .as_ref()
+            .is_some_and(|clock| clock.is_real_time());
+        if real_time {
+            self.clock_timeout()
+        } else {
+            None
+        }
+    }
+}
+
+/// The state shared by timers: when they started, and which deadline has
+/// been scheduled with the clock of the context they were read in.
+#[derive(Default)]
+pub(crate) struct TimerState {
+    meta: SyncWatchedMeta,
+    queue: RefCell<Weak<TimerQueue>>,
+    pub(crate) start: Cell<Option<Duration>>,
+    scheduled: Rc<Cell<Option<Duration>>>,
+}
+
+impl TimerState {
+    /// Remember the clock of the context, without binding the watch
+    /// closure.
+    pub fn bind_clock<O: ?Sized>(&self, ctx: WatchArg<'_, '_, O>) {
+        let mut queue = self.queue.borrow_mut();
+        if queue.strong_count() == 0 {
+            *queue = Weak::clone(&ctx.frame_info.timers);
+        }
+    }
+
+    /// Bind the watch closure, so it re-runs when a scheduled deadline is
+    /// reached, or the timer is restarted.
+    pub fn watched<O: ?Sized>(&self, ctx: WatchArg<'_, '_, O>) {
+        self.meta.watched(ctx);
+        self.bind_clock(ctx);
+    }
+
+    /// Get the time of the context the timer was last read in.
+    pub fn now(&self) -> Option<Duration> {
+        Some(self.queue.borrow().upgrade()?.now())
+    }
+
+    /// Bind the watch closure, returning the time since the timer started,
+    /// starting it if needed.
+    pub fn elapsed<O: ?Sized>(
+        &self,
+        ctx: WatchArg<'_, '_, O>,
+    ) -> Option<Duration> {
+        self.watched(ctx);
+        let now = self.now()?;
+        let start = self.start.get().unwrap_or(now);
+        self.start.set(Some(start));
+        Some(now - start)
+    }
+
+    /// Arrange for the bound watch closures to be re-run once the given
+    /// duration after the start has elapsed, replacing any deadline which
+    /// was scheduled before.
+    pub fn schedule(&self, after: Duration) {
+        let queue = self.queue.borrow().upgrade();
+        if let (Some(queue), Some(start)) = (queue, self.start.get()) {
+            let at = start + after;
+            if self.scheduled.get() != Some(at) {
+                self.scheduled.set(Some(at));
+                let trigger = self.meta.create_trigger();
+                queue.schedule(at, trigger, Rc::downgrade(&self.scheduled));
+            }
+        }
+    }
+
+    /// Stop the timer, cancelling any scheduled deadline.
+    pub fn stop(&self) {
+        self.start.set(None);
+        self.scheduled.set(None);
+    }
+
+    /// Stop the timer, and re-run the watch closures which read it.
+    pub fn restart(&self) {
+        self.stop();
+        self.meta.trigger();
+    }
+}
+
+/// A watched value which becomes true once a duration has elapsed,
+/// according to the clock of the context.
+///
+/// The timer starts the first time it is read by a watch function.  Watch
+/// functions which read it are re-run once the duration has elapsed; the
+/// context advances its clock on each update, so use
+/// [`WatchContext::next_deadline`](crate::WatchContext::next_deadline) to
+/// know when to next update it.
+///
+/// # Examples
+/// ```rust
+///# use core::time::Duration;
+///# use drying_paint::*;
+/// struct Owner {
+///     cursor: WatchedTimer,
+///     shown: bool,
+/// }
+///
+/// let mut ctx = WatchContext::from_owner(Owner {
+///     cursor: WatchedTimer::new(Duration::from_millis(500)),
+///     shown: true,
+/// });
+/// ctx.add_watch(|owner, arg| {
+///     if owner.cursor.get(arg) {
+///         owner.shown = !owner.shown;
+///         owner.cursor.restart();
+///     }
+/// });
+/// ctx.advance_time(Duration::from_millis(400));
+/// ctx.update();
+/// assert!(ctx.owner().shown);
+/// assert_eq!(ctx.next_deadline(), Some(Duration::from_millis(500)));
+/// ctx.advance_time(Duration::from_millis(500));
+/// ctx.update();
+/// assert!(!ctx.owner().shown);
+/// ```
+#[derive(Default)]
+pub struct WatchedTimer {
+    state: TimerState,
+    duration: Duration,
+    elapsed: Cell<bool>,
+}
+
+impl fmt::Debug for WatchedTimer {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("WatchedTimer")
+            .field("duration", &self.duration)
+            .field("elapsed", &self.elapsed.get())
+            .finish()
+    }
+}
+
+impl WatchedTimer {
+    /// Create a new timer which has not started yet.
+    pub fn new(duration: Duration) -> Self {
+        Self {
+            state: TimerState::default(),
+            duration,
+            elapsed: Cell::new(false),
+        }
+    }
+
+    /// Get the duration this timer waits for.
+    pub fn duration(&self) -> Duration {
+        self.duration
+    }
+
+    /// Returns true if the duration has elapsed, binding a watch closure.
+    /// Starts the timer if it has not started yet.
+    pub fn get<O: ?Sized>(&self, ctx: WatchArg<'_, '_, O>) -> bool {
+        if let Some(elapsed) = self.state.elapsed(ctx) {
+            let done = elapsed >= self.duration;
+            if !done {
+                self.state.schedule(self.duration);
+            }
+            self.elapsed.set(done);
+        }
+        self.get_unwatched()
+    }
+
+    /// Returns true if the duration had elapsed the last time this timer was
+    /// read by a watch function, without binding any watch closure.
+    pub fn get_unwatched(&self) -> bool {
+        self.elapsed.get()
+    }
+
+    #[cfg(feature = "std")]
+    pub fn get_auto(&self) -> bool {
+        WatchArg::<'_, 'static, DefaultOwner>::try_with_current(|arg| {
+            self.get(arg);
+        });
+        self.get_unwatched()
+    }
+
+    /// Reset the timer, so that it starts again the next time it is read by
+    /// a watch function.
+    pub fn restart(&mut self) {
+        self.elapsed.set(false);
+        self.state.restart();
+    }
+}
+
+/// A watched count of how many times a period has elapsed, according to
+/// the clock of the context.
+///
+/// Like [`WatchedTimer`], the interval starts the first time it is read by
+/// a watch function, and watch functions which read it are re-run each
+/// time the count increases.  If the context is not updated for several
+/// periods, the count increases by several at once.
+#[derive(Default)]
+pub struct WatchedInterval {
+    state: TimerState,
+    period: Duration,
+    count: Cell<u64>,
+}
+
+impl fmt::Debug for WatchedInterval {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("WatchedInterval")
+            .field("period", &self.period)
+            .field("count", &self.count.get())
+            .finish()
+    }
+}
+
+impl WatchedInterval {
+    /// Create a new interval which has not started yet.
+    ///
+    /// # Panics
+    /// Panics if `period` is zero.
+    pub fn new(period: Duration) -> Self {
+        assert!(period > Duration::ZERO, "interval period must be non-zero");
+        Self {
+            state: TimerState::default(),
+            period,
+            count: Cell::new(0),
+        }
+    }
+
+    /// Get the period of this interval.
+    pub fn period(&self) -> Duration {
+        self.period
+    }
+
+    /// Get the number of periods which have elapsed, binding a watch
+    /// closure.  Starts the interval if it has not started yet.
+    pub fn get<O: ?Sized>(&self, ctx: WatchArg<'_, '_, O>) -> u64 {
+        if let Some(elapsed) = self.state.elapsed(ctx) {
+            let count = elapsed.as_nanos() / self.period.as_nanos().max(1);
+            let count = u64::try_from(count).unwrap_or(u64::MAX);
+            let next = u32::try_from(count.saturating_add(1))
+                .ok()
+                .and_then(|next| self.period.checked_mul(next));
+            if let Some(next) = next {
+                self.state.schedule(next);
+            }
+            self.count.set(count);
+        }
+        self.get_unwatched()
+    }
+
+    /// Get the number of periods which had elapsed the last time this
+    /// interval was read by a watch function, without binding any watch
+    /// closure.
+    pub fn get_unwatched(&self) -> u64 {
+        self.count.get()
+    }
+
+    #[cfg(feature = "std")]
+    pub fn get_auto(&self) -> u64 {
+        WatchArg::<'_, 'static, DefaultOwner>::try_with_current(|arg| {
+            self.get(arg);
+        });
+        self.get_unwatched()
+    }
+
+    /// Reset the count to zero, starting again the next time the interval
+    /// is read by a watch function.
+    pub fn restart(&mut self) {
+        self.count.set(0);
+        self.state.restart();
+    }
+}
+
+#[cfg(all(test, feature = "std"))]
+mod tests {
+    use core::time::Duration;
+
+    use crate::*;
+
+    #[test]
+    fn interval_counts_periods() {
+        #[derive(Default)]
+        struct Owner {
+            tick: WatchedInterval,
+            runs: u32,
+        }
+
+        let ms = Duration::from_millis;
+        let mut ctx = WatchContext::from_owner(Owner {
+            tick: WatchedInterval::new(ms(10)),
+            runs: 0,
+        });
+        ctx.add_watch(|owner, arg| {
+            owner.tick.get(arg);
+            owner.runs += 1;
+        });
+        ctx.advance_time(ms(5));
+        ctx.update();
+        assert_eq!(ctx.owner().runs, 1);
+        assert_eq!(ctx.next_deadline(), Some(ms(10)));
+        ctx.advance_time(ms(10));
+        ctx.update();
+        assert_eq!(ctx.owner().runs, 2);
+        assert_eq!(ctx.owner().tick.get_unwatched(), 1);
+        ctx.advance_time(ms(35));
+        ctx.update();
+        assert_eq!(ctx.owner().runs, 3);
+        assert_eq!(ctx.owner().tick.get_unwatched(), 3);
+        assert_eq!(ctx.next_deadline(), Some(ms(40)));
+        ctx.owner().tick.restart();
+        ctx.advance_time(ms(36));
+        ctx.update();
+        assert_eq!(ctx.owner().runs, 4);
+        assert_eq!(ctx.owner().tick.get_unwatched(), 0);
+        assert_eq!(ctx.next_deadline(), Some(ms(46)));
+        ctx.advance_time(ms(40));
+        ctx.update();
+        assert_eq!(ctx.owner().runs, 4);
+    }
+
+    #[test]
+    fn manual_clock_deadline_pending() {
+        struct Owner {
+            timeout: WatchedTimer,
+            runs: u32,
+        }
+
+        let secs = Duration::from_secs;
+        let clock = ManualClock::new();
+        let mut ctx = WatchContext::from_owner(Owner {
+            timeout: WatchedTimer::new(secs(5)),
+            runs: 0,
+        });
+        ctx.set_clock(clock.clone());
+        ctx.add_watch(|owner, arg| {
+            owner.timeout.get(arg);
+            owner.runs += 1;
+        });
+        clock.advance(secs(4));
+        assert!(!ctx.wait_for_sync(Some(Duration::ZERO)));
+        clock.advance(secs(1));
+        assert!(ctx.wait_for_sync(Some(Duration::ZERO)));
+        ctx.update();
+        assert_eq!(ctx.owner().runs, 2);
+        assert_eq!(ctx.now(), secs(5));
+        assert_eq!(ctx.next_deadline(), None);
+    }
+
+    #[test]
+    fn updates_pending_woken_by_timer() {
+        use std::{
+            future::Future,
+            pin::pin,
+            sync::Arc,
+            task::{Context, Poll, Wake, Waker},
+            thread::{self, Thread},
+            time::Instant,
+        };
+
+        struct Unpark(Thread);
+
+        impl Wake for Unpark {
+            fn wake(self: Arc<Self>) {
+                self.0.unpark();
+            }
+        }
+
+        struct Owner {
+            timeout: WatchedTimer,
+            fired: bool,
+        }
+
+        let mut ctx = WatchContext::from_owner(Owner {
+            timeout: WatchedTimer::new(Duration::from_millis(30)),
+            fired: false,
+        });
+        ctx.add_watch(|owner, arg| {
+            owner.fired = owner.timeout.get(arg);
+        });
+        let waker = Waker::from(Arc::new(Unpark(thread::current())));
+        let mut cx = Context::from_waker(&waker);
+        // nothing else happens, so only the timer can wake this loop
+        let give_up = Instant::now() + Duration::from_secs(5);
+        while !ctx.owner().fired {
+            assert!(Instant::now() < give_up, "never woken by the timer");
+            match pin!(ctx.updates_pending()).poll(&mut cx) {
+                Poll::Ready(()) => ctx.update(),
+                Poll::Pending => thread::park_timeout(Duration::from_secs(10)),
+            }
+        }
+    }
+
+    #[test]
+    fn standing_clock_not_slept_on() {
+        use std::{cell::Cell, rc::Rc};
+
+        #[derive(Clone, Default)]
+        struct CountReads(Rc<Cell<u32>>);
+
+        impl Clock for CountReads {
+            fn now(&self) -> Duration {
+                self.0.set(self.0.get() + 1);
+                Duration::ZERO
+            }
+        }
+
+        struct Owner {
+            timeout: WatchedTimer,
+            value: std::sync::Arc<SyncWatchedAtomicU32>,
+            seen: u32,
+        }
+
+        let clock = CountReads::default();
+        let mut ctx = WatchContext::from_owner(Owner {
+            timeout: WatchedTimer::new(Duration::from_millis(1)),
+            value: Default::default(),
+            seen: 0,
+        });
+        ctx.set_clock(clock.clone());
+        ctx.add_watch(|owner, arg| {
+            owner.timeout.get(arg);
+            owner.seen = owner.value.get(arg);
+        });
+        let value = std::sync::Arc::clone(&ctx.owner().value);
+        let thread = std::thread::spawn(move || {
+            std::thread::sleep(Duration::from_millis(50));
+            value.store(7);
+        });
+        clock.0.set(0);
+        // the timer is never due while this clock stands still, so the
+        // thread should sleep until the store instead of waking every
+        // millisecond to look at the clock
+        assert!(ctx.wait_for_sync(None));
+        assert!(clock.0.get() < 10);
+        thread.join().unwrap();
+        ctx.update();
+        assert_eq!(ctx.owner().seen, 7);
+        assert_eq!(ctx.next_deadline(), Some(Duration::from_millis(1)));
+    }
+}
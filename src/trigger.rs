@@ -14,6 +14,7 @@ use crate::context::{FrameInfo, WatchContext};
 
 struct WatchData<F: ?Sized> {
     cycle: Cell<usize>,
+    sticky: Cell<bool>,
     #[cfg_attr(not(do_cycle_debug), allow(dead_code))]
     debug_name: WatchName,
     update_fn: F,
@@ -195,6 +196,12 @@ impl<'a, 'ctx, O: ?Sized> RawWatchArg<'a, 'ctx, O> {
         self.ctx
     }
 
+    /// Stop this watch from running again, regardless of what it has
+    /// watched so far.
+    pub(crate) fn cancel(&self) {
+        self.watch.cancel();
+    }
+
     pub fn as_owner_and_arg(&mut self) -> (&mut O, WatchArg<'_, 'ctx, O>) {
         let Self { ctx, watch } = self;
         let WatchContext {
@@ -234,10 +241,32 @@ impl<'ctx, O: ?Sized> Watch<'ctx, O> {
             update_fn,
             debug_name,
             cycle: Cell::new(0),
+            sticky: Cell::new(false),
         }));
         this.get_ref().execute(ctx);
     }
 
+    pub(crate) fn cancel(&self) {
+        // invalidates every outstanding WatchRef
+        let cycle = &self.0.cycle;
+        cycle.set(cycle.get().wrapping_add(1));
+    }
+
+    /// Keep this watch in the watch lists of the values it reads, even
+    /// once they change, so that it may skip a run without losing track of
+    /// them.
+    pub(crate) fn set_sticky(&self) {
+        self.0.sticky.set(true);
+    }
+
+    /// Treat the current run as though it never started, so the values
+    /// read in the previous run stay watched.  This must be called before
+    /// anything is read in the current run.
+    pub(crate) fn skip_run(&self) {
+        let cycle = &self.0.cycle;
+        cycle.set(cycle.get().wrapping_sub(1));
+    }
+
     pub(crate) fn get_ref(&self) -> WatchRef<'ctx, O> {
         WatchRef {
             watch: self.clone(),
@@ -251,6 +280,15 @@ pub(crate) struct WatchRef<'ctx, O: ?Sized> {
     cycle: usize,
 }
 
+impl<'ctx, O: ?Sized> Clone for WatchRef<'ctx, O> {
+    fn clone(&self) -> Self {
+        Self {
+            watch: self.watch.clone(),
+            cycle: self.cycle,
+        }
+    }
+}
+
 impl<'ctx, O: ?Sized> WatchRef<'ctx, O> {
     pub fn watch_eq(&self, other: &Watch<'ctx, O>) -> bool {
         Rc::ptr_eq(&self.watch.0, &other.0)
@@ -260,6 +298,10 @@ impl<'ctx, O: ?Sized> WatchRef<'ctx, O> {
         self.cycle == self.watch.0.cycle.get()
     }
 
+    fn is_sticky(&self) -> bool {
+        self.watch.0.sticky.get() && self.is_fresh()
+    }
+
     fn execute(self, ctx: &mut WatchContext<'ctx, O>) {
         if self.is_fresh() {
             self.watch.0.cycle.set(self.cycle.wrapping_add(1));
@@ -359,42 +401,110 @@ impl<'ctx, O: ?Sized> Default for WatchSetNode<'ctx, O> {
     }
 }
 
-struct WatchSetHead<'ctx, O: ?Sized> {
+/// State kept along with the watches of a set, which is dropped along
+/// with them when the set is triggered, unless it is not empty.
+pub(crate) trait SetExtra: Default {
+    fn is_empty(&self) -> bool;
+}
+
+impl SetExtra for () {
+    fn is_empty(&self) -> bool {
+        true
+    }
+}
+
+struct WatchSetHead<'ctx, O: ?Sized, X> {
     node: WatchSetNode<'ctx, O>,
     target: Weak<WatchFrame<'ctx, O>>,
     nodes: u32,
+    extra: X,
 }
 
-impl<'ctx, O: ?Sized> Default for WatchSetHead<'ctx, O> {
-    fn default() -> Self {
+impl<'ctx, O: ?Sized, X: Default> WatchSetHead<'ctx, O, X> {
+    fn new(target: Weak<WatchFrame<'ctx, O>>) -> Self {
         Self {
             node: WatchSetNode::default(),
-            target: Weak::default(),
+            target,
             nodes: 1,
+            extra: X::default(),
         }
     }
 }
 
-pub(crate) struct WatchSet<'ctx, O: ?Sized> {
-    list: Cell<Option<Box<WatchSetHead<'ctx, O>>>>,
+pub(crate) struct WatchSet<'ctx, O: ?Sized, X: SetExtra = ()> {
+    list: Cell<Option<Box<WatchSetHead<'ctx, O, X>>>>,
 }
 
-impl<'ctx, O: ?Sized> Default for WatchSet<'ctx, O> {
+impl<'ctx, O: ?Sized, X: SetExtra> Default for WatchSet<'ctx, O, X> {
     fn default() -> Self {
         Self::new()
     }
 }
 
-impl<'ctx, O: ?Sized> WatchSet<'ctx, O> {
+impl<'ctx, O: ?Sized, X: SetExtra> Drop for WatchSet<'ctx, O, X> {
+    #[cfg_attr(do_cycle_debug, track_caller)]
+    fn drop(&mut self) {
+        self.trigger_external();
+    }
+}
+
+impl<'ctx, O: ?Sized, X: SetExtra> WatchSet<'ctx, O, X> {
     pub fn new() -> Self {
         WatchSet {
             list: Cell::new(None),
         }
     }
 
+    /// Bind the watch closure, so it is re-run when this set is triggered.
+    pub fn watched(&self, ctx: WatchArg<'_, 'ctx, O>) {
+        self.add(
+            ctx.watch.get_ref(),
+            &ctx.frame_info.post_set,
+            ctx.total_watch_count,
+        );
+    }
+
+    #[cfg_attr(do_cycle_debug, track_caller)]
+    pub fn trigger(&self, ctx: WatchArg<'_, 'ctx, O>) {
+        let reason = TriggerReason::from_caller().with_source(ctx.watch);
+        self.trigger_with_current(ctx.watch, reason);
+    }
+
+    #[cfg_attr(do_cycle_debug, track_caller)]
+    pub fn trigger_external(&self) {
+        let reason = TriggerReason::from_caller();
+        self.trigger_filtered(reason, |_| true);
+    }
+
+    /// Access the extra state of this set, if it has any watches.
+    pub fn with_extra<F, R>(&self, func: F) -> Option<R>
+    where
+        F: FnOnce(&mut X) -> R,
+    {
+        self.with(|list| list.as_mut().map(|head| func(&mut head.extra)))
+    }
+
+    /// Access the extra state of this set, ready for the watch closure to
+    /// be added.
+    pub fn with_extra_for<F, R>(
+        &self,
+        ctx: WatchArg<'_, 'ctx, O>,
+        func: F,
+    ) -> R
+    where
+        F: FnOnce(&mut X) -> R,
+    {
+        self.with(|list| {
+            let head = list.get_or_insert_with(|| {
+                Box::new(WatchSetHead::new(ctx.frame_info.post_set.clone()))
+            });
+            func(&mut head.extra)
+        })
+    }
+
     fn with<F, R>(&self, func: F) -> R
     where
-        F: FnOnce(&mut Option<Box<WatchSetHead<'ctx, O>>>) -> R,
+        F: FnOnce(&mut Option<Box<WatchSetHead<'ctx, O, X>>>) -> R,
     {
         let mut list = self.list.replace(None);
         let ret = func(&mut list);
@@ -416,11 +526,7 @@ impl<'ctx, O: ?Sized> WatchSet<'ctx, O> {
         let mut squash = false;
         self.with(|list| {
             let head = list.get_or_insert_with(|| {
-                Box::new(WatchSetHead {
-                    node: WatchSetNode::default(),
-                    target: target.clone(),
-                    nodes: 1,
-                })
+                Box::new(WatchSetHead::new(target.clone()))
             });
             squash = head.node.data[0].is_none() && head.nodes > node_limit;
             let new_cycle = watch.cycle;
@@ -450,11 +556,15 @@ impl<'ctx, O: ?Sized> WatchSet<'ctx, O> {
         if let Some(head) = self.list.take() {
             if let Some(target_box) = head.target.upgrade() {
                 let mut target = target_box.take();
+                let mut sticky = Vec::new();
                 let mut node = head.node;
                 loop {
                     for bucket in node.data.iter_mut() {
                         if let Some(watch) = bucket.take().filter(&mut filter)
                         {
+                            if watch.is_sticky() {
+                                sticky.push(watch.clone());
+                            }
                             target.push(TriggeredWatch { watch, reason })
                         }
                     }
@@ -465,6 +575,14 @@ impl<'ctx, O: ?Sized> WatchSet<'ctx, O> {
                     };
                 }
                 target_box.set(target);
+                if !head.extra.is_empty() {
+                    let mut kept = WatchSetHead::new(head.target.clone());
+                    kept.extra = head.extra;
+                    self.list.set(Some(Box::new(kept)));
+                }
+                for watch in sticky {
+                    self.add(watch, &head.target, usize::MAX);
+                }
             }
         }
     }
@@ -477,8 +595,23 @@ impl<'ctx, O: ?Sized> WatchSet<'ctx, O> {
         self.trigger_filtered(reason, |to_add| !to_add.watch_eq(current));
     }
 
-    pub fn trigger_external(&self, reason: TriggerReason) {
-        self.trigger_filtered(reason, |_| true);
+    /// Returns true if any watch in this set would still be re-run by it.
+    pub fn has_fresh(&self) -> bool {
+        self.with(|list| {
+            let mut node = match list {
+                Some(head) => &head.node,
+                None => return false,
+            };
+            loop {
+                if node.data.iter().flatten().any(WatchRef::is_fresh) {
+                    return true;
+                }
+                node = match &node.next {
+                    Some(next) => next,
+                    None => return false,
+                };
+            }
+        })
     }
 
     pub fn squash(&self) {
@@ -519,3 +652,22 @@ impl<'ctx, O: ?Sized> WatchSet<'ctx, O> {
         });
     }
 }
+
+#[cfg(feature = "std")]
+impl<X: SetExtra> WatchSet<'static, crate::DefaultOwner, X> {
+    pub fn watched_auto(&self) {
+        WatchArg::try_with_current(|arg| self.watched(arg));
+    }
+
+    #[cfg_attr(do_cycle_debug, track_caller)]
+    pub fn trigger_auto(&self) {
+        let reason = TriggerReason::from_caller();
+        let found_current = WatchArg::try_with_current(|arg| {
+            let reason = reason.with_source(arg.watch);
+            self.trigger_with_current(arg.watch, reason)
+        });
+        if found_current.is_none() {
+            self.trigger_filtered(reason, |_| true);
+        }
+    }
+}
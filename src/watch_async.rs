@@ -0,0 +1,193 @@
+/* SPDX-License-Identifier: (Apache-2.0 OR MIT OR Zlib) */
+/* Copyright © 2026 Violet Leonard */
+
+use {
+    alloc::{boxed::Box, rc::Rc, sync::Arc, task::Wake},
+    core::{
+        cell::Cell,
+        future::Future,
+        pin::Pin,
+        task::{Context, Poll, Waker},
+    },
+};
+
+use crate::{
+    SyncTrigger, SyncWatchedMeta, WatchArg, WatchContext, WatchName,
+    WatcherHolder,
+};
+
+struct TriggerWaker(SyncTrigger);
+
+impl Wake for TriggerWaker {
+    fn wake(self: Arc<Self>) {
+        self.0.trigger();
+    }
+
+    fn wake_by_ref(self: &Arc<Self>) {
+        self.0.trigger();
+    }
+}
+
+struct InFlight<Fut> {
+    future: Cell<Option<Pin<Box<Fut>>>>,
+    woken: SyncWatchedMeta,
+    waker: Waker,
+}
+
+impl<Fut: Future> InFlight<Fut> {
+    fn is_idle(&self) -> bool {
+        let future = self.future.take();
+        let idle = future.is_none();
+        self.future.set(future);
+        idle
+    }
+
+    fn poll<T: ?Sized>(&self, item: &mut T)
+    where
+        Fut::Output: FnOnce(&mut T),
+    {
+        if let Some(mut future) = self.future.take() {
+            let mut cx = Context::from_waker(&self.waker);
+            match future.as_mut().poll(&mut cx) {
+                Poll::Ready(apply) => apply(item),
+                Poll::Pending => self.future.set(Some(future)),
+            }
+        }
+    }
+}
+
+/// Set up a pair of watches: one which re-runs `func` whenever the values
+/// it reads change, replacing the in-flight future, and one which polls
+/// that future again whenever it is woken.
+pub(crate) fn spawn_async_watch<'ctx, O, P, F, Fut>(
+    ctx: &mut WatchContext<'ctx, O>,
+    debug_name: WatchName,
+    path: &P,
+    func: F,
+) where
+    O: ?Sized,
+    P: 'ctx + WatcherHolder<'ctx, O>,
+    F: 'ctx + Fn(WatchArg<'_, 'ctx, O>, &mut P::Content) -> Fut,
+    Fut: 'ctx + Future,
+    Fut::Output: FnOnce(&mut P::Content),
+{
+    let woken = SyncWatchedMeta::new();
+    let waker = Waker::from(Arc::new(TriggerWaker(woken.create_trigger())));
+    let in_flight = Rc::new(InFlight {
+        future: Cell::new(None),
+        woken,
+        waker,
+    });
+    let started = Rc::new(());
+
+    // added first, so the future can be woken as soon as it is polled
+    let poll_in_flight = Rc::clone(&in_flight);
+    let poll_started = Rc::downgrade(&started);
+    let poll_path = path.clone();
+    ctx.add_watch_raw(debug_name, move |mut raw_arg| {
+        if poll_in_flight.is_idle() && poll_started.strong_count() == 0 {
+            // nothing will be started or woken any more
+            return;
+        }
+        let (owner, arg) = raw_arg.as_owner_and_arg();
+        poll_in_flight.woken.watched(arg);
+        poll_path.get_mut(owner, |item| poll_in_flight.poll(item));
+    });
+
+    let start_path = path.clone();
+    ctx.add_watch_raw(debug_name, move |mut raw_arg| {
+        let _started = &started;
+        let (owner, arg) = raw_arg.as_owner_and_arg();
+        start_path.get_mut(owner, |item| {
+            // dropping the previous future cancels it
+            in_flight.future.set(Some(Box::pin(func(arg, item))));
+            in_flight.poll(item);
+        });
+    });
+}
+
+#[cfg(all(test, feature = "std"))]
+mod tests {
+    use std::{
+        cell::RefCell,
+        future::Future,
+        pin::Pin,
+        rc::Rc,
+        sync::{Arc, Mutex},
+        task::{Context, Poll, Waker},
+        thread,
+        vec::Vec,
+    };
+
+    use crate::*;
+
+    type Slot = Arc<Mutex<(Option<u32>, Option<Waker>)>>;
+
+    fn fill(slot: &Slot, value: u32) {
+        let mut slot = slot.lock().unwrap();
+        slot.0 = Some(value);
+        if let Some(waker) = slot.1.take() {
+            waker.wake();
+        }
+    }
+
+    struct Reply(Slot);
+
+    impl Future for Reply {
+        type Output = u32;
+
+        fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<u32> {
+            let mut slot = self.0.lock().unwrap();
+            if let Some(value) = slot.0.take() {
+                Poll::Ready(value)
+            } else {
+                slot.1 = Some(cx.waker().clone());
+                Poll::Pending
+            }
+        }
+    }
+
+    struct Lookup {
+        query: Watched<u32>,
+        requests: Vec<Slot>,
+        results: Vec<u32>,
+    }
+
+    impl Watcher<'static> for Lookup {
+        fn init(mut init: impl WatcherInit<'static, Self>) {
+            init.watch_async(|root| {
+                let query = *root.query;
+                let slot = Slot::default();
+                root.requests.push(Arc::clone(&slot));
+                async move {
+                    let value = Reply(slot).await;
+                    move |root: &mut Lookup| root.results.push(query * value)
+                }
+            });
+        }
+    }
+
+    #[test]
+    fn stale_lookup_dropped() {
+        let lookup = Rc::new(RefCell::new(Lookup {
+            query: Watched::new(1),
+            requests: Vec::new(),
+            results: Vec::new(),
+        }));
+        let mut ctx = WatchContext::new();
+        ctx.add_watcher(&Rc::downgrade(&lookup));
+        *lookup.borrow_mut().query = 2;
+        ctx.update();
+        assert_eq!(lookup.borrow().requests.len(), 2);
+        // the first lookup was dropped when the query changed
+        assert_eq!(Arc::strong_count(&lookup.borrow().requests[0]), 1);
+        let requests = lookup.borrow().requests.clone();
+        let second = Arc::clone(&requests[1]);
+        thread::spawn(move || fill(&second, 10)).join().unwrap();
+        ctx.update();
+        assert_eq!(lookup.borrow().results, [20]);
+        fill(&requests[0], 10);
+        ctx.update();
+        assert_eq!(lookup.borrow().results, [20]);
+    }
+}
@@ -3,6 +3,7 @@
 
 use std::{
     fmt,
+    hash::Hash,
     ops::{Deref, DerefMut},
 };
 
@@ -24,6 +25,12 @@ impl<T> Watched<T> {
         }
     }
 
+    pub(crate) fn core_mut(
+        this: &mut Self,
+    ) -> &mut WatchedCore<'static, T, DefaultOwner> {
+        &mut this.inner
+    }
+
     /// Consumes the `Watched`, returning the wrapped value
     pub fn into_inner(this: Self) -> T {
         this.inner.into_inner()
@@ -31,16 +38,81 @@ impl<T> Watched<T> {
 
     /// Replaces the wrapped value with a new one, returning the old value,
     /// without deinitializing either one.
+    #[cfg_attr(do_cycle_debug, track_caller)]
     pub fn replace(this: &mut Self, value: T) -> T {
-        std::mem::replace(&mut *this, value)
+        this.inner.replace_auto(value)
     }
 
     /// Takes the wrapped value, leaving `Default::default()` in its place.
+    #[cfg_attr(do_cycle_debug, track_caller)]
     pub fn take(this: &mut Self) -> T
     where
         T: Default,
     {
-        std::mem::take(&mut *this)
+        this.inner.take_auto()
+    }
+
+    /// Change the wrapped value in place.  Unlike changing it through
+    /// `DerefMut`, watch functions which used [`select`](Self::select)
+    /// are only re-run if what they selected has changed.
+    #[cfg_attr(do_cycle_debug, track_caller)]
+    pub fn modify<F, R>(this: &mut Self, func: F) -> R
+    where
+        F: FnOnce(&mut T) -> R,
+    {
+        this.inner.modify_auto(func)
+    }
+
+    /// Get part of the wrapped value, so that the current watch function
+    /// is only re-run when that part changes.  See
+    /// [`WatchedCore::select`].
+    ///
+    /// # Examples
+    /// ```rust
+    ///# use std::{rc::Rc, cell::RefCell};
+    ///# use drying_paint::{Watcher, Watched, WatcherInit, WatchContext};
+    /// #[derive(Clone, Default)]
+    /// struct Config {
+    ///     volume: u8,
+    ///     theme: String,
+    /// }
+    ///
+    /// #[derive(Default)]
+    /// struct Player {
+    ///     config: Watched<Config>,
+    ///     volume_changes: u32,
+    /// }
+    ///
+    /// impl Watcher<'static> for Player {
+    ///     fn init(mut init: impl WatcherInit<'static, Self>) {
+    ///         init.watch(|root| {
+    ///             Watched::select(&root.config, |config| config.volume);
+    ///             root.volume_changes += 1;
+    ///         });
+    ///     }
+    /// }
+    ///
+    /// let player = Rc::new(RefCell::new(Player::default()));
+    /// let mut ctx = WatchContext::new();
+    /// ctx.add_watcher(&Rc::downgrade(&player));
+    /// Watched::modify(&mut player.borrow_mut().config, |config| {
+    ///     config.theme = "dark".into();
+    /// });
+    /// ctx.update();
+    /// assert_eq!(player.borrow().volume_changes, 1);
+    /// Watched::modify(&mut player.borrow_mut().config, |config| {
+    ///     config.volume = 11;
+    /// });
+    /// ctx.update();
+    /// assert_eq!(player.borrow().volume_changes, 2);
+    /// ```
+    pub fn select<F, V>(this: &Self, func: F) -> V
+    where
+        T: 'static,
+        F: 'static + Fn(&T) -> V,
+        V: 'static + Clone + PartialEq,
+    {
+        this.inner.select_auto(func)
     }
 
     /// This function provides a way to set a value for a watched value
@@ -95,6 +167,67 @@ impl<T> Watched<T> {
     {
         wrapper.inner.set_if_neq_auto(value);
     }
+
+    /// Like [`set_if_neq`](Self::set_if_neq), but compares the hash of the
+    /// values, for types which are not `PartialEq`, or are expensive to
+    /// compare.
+    #[cfg_attr(do_cycle_debug, track_caller)]
+    pub fn set_if_hash_neq(wrapper: &mut Watched<T>, value: T)
+    where
+        T: Hash,
+    {
+        wrapper.inner.set_if_hash_neq_auto(value);
+    }
+
+    /// Change the wrapped value in place, only notifying watchers if its
+    /// hash changed.
+    #[cfg_attr(do_cycle_debug, track_caller)]
+    pub fn modify_if_hash_changed<F, R>(this: &mut Self, func: F) -> R
+    where
+        T: Hash,
+        F: FnOnce(&mut T) -> R,
+    {
+        this.inner.modify_if_hash_changed_auto(func)
+    }
+
+    /// Change the wrapped value in place, only notifying watchers if the
+    /// result of `fingerprint` is different afterwards than it was before.
+    ///
+    /// # Examples
+    /// ```rust
+    ///# use drying_paint::Watched;
+    /// struct Samples {
+    ///     revision: u64,
+    ///     data: Vec<f32>,
+    /// }
+    ///
+    /// let mut samples = Watched::new(Samples {
+    ///     revision: 0,
+    ///     data: Vec::new(),
+    /// });
+    /// Watched::modify_if_fingerprint_changed(
+    ///     &mut samples,
+    ///     |samples| samples.revision,
+    ///     |samples| {
+    ///         samples.data.push(0.5);
+    ///         samples.revision += 1;
+    ///     },
+    /// );
+    /// ```
+    #[cfg_attr(do_cycle_debug, track_caller)]
+    pub fn modify_if_fingerprint_changed<P, K, F, R>(
+        this: &mut Self,
+        fingerprint: P,
+        func: F,
+    ) -> R
+    where
+        P: Fn(&T) -> K,
+        K: PartialEq,
+        F: FnOnce(&mut T) -> R,
+    {
+        this.inner
+            .modify_if_fingerprint_changed_auto(fingerprint, func)
+    }
 }
 
 impl<T: ?Sized> Watched<T> {
@@ -394,6 +527,71 @@ where
     }
 }
 
+#[cfg(feature = "serde")]
+mod serde_impls {
+    use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+    use super::{Watched, WatchedCell};
+
+    /// Serializes the value without binding the current watch closure.
+    impl<T: ?Sized + Serialize> Serialize for Watched<T> {
+        fn serialize<S: Serializer>(
+            &self,
+            serializer: S,
+        ) -> Result<S::Ok, S::Error> {
+            self.inner.serialize(serializer)
+        }
+    }
+
+    /// Deserializing in place triggers any watch closures bound to the
+    /// value, without binding the current one.  If deserializing fails,
+    /// the value is left unchanged.
+    impl<'de, T: Deserialize<'de>> Deserialize<'de> for Watched<T> {
+        fn deserialize<D: Deserializer<'de>>(
+            deserializer: D,
+        ) -> Result<Self, D::Error> {
+            T::deserialize(deserializer).map(Self::new)
+        }
+
+        fn deserialize_in_place<D: Deserializer<'de>>(
+            deserializer: D,
+            place: &mut Self,
+        ) -> Result<(), D::Error> {
+            place.inner.set_auto(T::deserialize(deserializer)?);
+            Ok(())
+        }
+    }
+
+    /// Serializes the value without binding the current watch closure.
+    impl<T: Copy + Serialize> Serialize for WatchedCell<T> {
+        fn serialize<S: Serializer>(
+            &self,
+            serializer: S,
+        ) -> Result<S::Ok, S::Error> {
+            self.inner.serialize(serializer)
+        }
+    }
+
+    /// Deserializing in place triggers any watch closures bound to the
+    /// value, without binding the current one.  If deserializing fails,
+    /// the value is left unchanged.
+    impl<'de, T: Deserialize<'de>> Deserialize<'de> for WatchedCell<T> {
+        fn deserialize<D: Deserializer<'de>>(
+            deserializer: D,
+        ) -> Result<Self, D::Error> {
+            T::deserialize(deserializer).map(Self::new)
+        }
+
+        fn deserialize_in_place<D: Deserializer<'de>>(
+            deserializer: D,
+            place: &mut Self,
+        ) -> Result<(), D::Error> {
+            place.inner.set_auto(T::deserialize(deserializer)?);
+            Ok(())
+        }
+    }
+}
+
 pub trait WatchedValue:
     crate::WatchedValueCore<'static, DefaultOwner>
 {
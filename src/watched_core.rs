@@ -1,10 +1,14 @@
 /* SPDX-License-Identifier: (Apache-2.0 OR MIT OR Zlib) */
 /* Copyright © 2021 Violet Leonard */
 
-use core::cell::Cell;
+use {
+    alloc::{boxed::Box, vec::Vec},
+    core::{cell::Cell, hash::Hash},
+};
 
 use crate::{
-    trigger::{TriggerReason, WatchArg, WatchSet},
+    fnv::hash_value,
+    trigger::{SetExtra, WatchArg, WatchSet},
     DefaultOwner,
 };
 
@@ -24,13 +28,6 @@ impl<'ctx, O: ?Sized> Default for WatchedMeta<'ctx, O> {
     }
 }
 
-impl<'ctx, O: ?Sized> Drop for WatchedMeta<'ctx, O> {
-    #[cfg_attr(do_cycle_debug, track_caller)]
-    fn drop(&mut self) {
-        self.trigger_external();
-    }
-}
-
 impl<'ctx, O: ?Sized> WatchedMeta<'ctx, O> {
     /// Create a new WatchedMeta instance
     pub fn new() -> Self {
@@ -42,44 +39,53 @@ impl<'ctx, O: ?Sized> WatchedMeta<'ctx, O> {
     /// When run in a function designed to watch a value, will bind so that
     /// function will be re-run when this is triggered.
     pub fn watched(&self, ctx: WatchArg<'_, 'ctx, O>) {
-        self.watchers.add(
-            ctx.watch.get_ref(),
-            &ctx.frame_info.post_set,
-            ctx.total_watch_count,
-        );
+        self.watchers.watched(ctx);
     }
 
     /// Mark this value as having changed, so that watching functions will
     /// be marked as needing to be updated.
     #[cfg_attr(do_cycle_debug, track_caller)]
     pub fn trigger(&self, ctx: WatchArg<'_, 'ctx, O>) {
-        let reason = TriggerReason::from_caller().with_source(ctx.watch);
-        self.watchers.trigger_with_current(ctx.watch, reason);
+        self.watchers.trigger(ctx);
     }
 
     #[cfg_attr(do_cycle_debug, track_caller)]
     pub fn trigger_external(&self) {
-        let reason = TriggerReason::from_caller();
-        self.watchers.trigger_external(reason);
+        self.watchers.trigger_external();
     }
 }
 
 #[cfg(feature = "std")]
 impl WatchedMeta<'static, DefaultOwner> {
     pub fn watched_auto(&self) {
-        WatchArg::try_with_current(|arg| self.watched(arg));
+        self.watchers.watched_auto();
     }
 
     #[cfg_attr(do_cycle_debug, track_caller)]
     pub fn trigger_auto(&self) {
-        let reason = TriggerReason::from_caller();
-        let found_current = WatchArg::try_with_current(|arg| {
-            let reason = reason.with_source(arg.watch);
-            self.watchers.trigger_with_current(arg.watch, reason)
-        });
-        if found_current.is_none() {
-            self.watchers.trigger_external(reason);
-        }
+        self.watchers.trigger_auto();
+    }
+}
+
+/// What one run of a watch function selected from a value with
+/// [`WatchedCore::select`], so that it is only notified once that changes.
+struct Memo<'ctx, T: ?Sized, O: ?Sized> {
+    watchers: WatchSet<'ctx, O>,
+    changed: Box<dyn 'ctx + Fn(&T) -> bool>,
+}
+
+/// The memos of a [`WatchedCore`], kept with its watchers.
+struct Memos<'ctx, T: ?Sized, O: ?Sized>(Vec<Memo<'ctx, T, O>>);
+
+impl<'ctx, T: ?Sized, O: ?Sized> Default for Memos<'ctx, T, O> {
+    fn default() -> Self {
+        Self(Vec::new())
+    }
+}
+
+impl<'ctx, T: ?Sized, O: ?Sized> SetExtra for Memos<'ctx, T, O> {
+    fn is_empty(&self) -> bool {
+        self.0.is_empty()
     }
 }
 
@@ -87,7 +93,7 @@ impl WatchedMeta<'static, DefaultOwner> {
 /// functions that reference this value will be re-run when this value
 /// changes.
 pub struct WatchedCore<'ctx, T: ?Sized, O: ?Sized = DefaultOwner> {
-    meta: WatchedMeta<'ctx, O>,
+    meta: WatchSet<'ctx, O, Memos<'ctx, T, O>>,
     value: T,
 }
 
@@ -108,7 +114,7 @@ impl<'ctx, T, O: ?Sized> WatchedCore<'ctx, T, O> {
     pub fn new(value: T) -> Self {
         Self {
             value,
-            meta: WatchedMeta::new(),
+            meta: WatchSet::new(),
         }
     }
 
@@ -122,12 +128,12 @@ impl<'ctx, T, O: ?Sized> WatchedCore<'ctx, T, O> {
     /// value has changed.
     #[cfg_attr(do_cycle_debug, track_caller)]
     pub fn replace(&mut self, value: T, ctx: WatchArg<'_, 'ctx, O>) -> T {
-        core::mem::replace(self.get_mut(ctx), value)
+        self.modify(ctx, |current| core::mem::replace(current, value))
     }
 
     #[cfg_attr(do_cycle_debug, track_caller)]
     pub fn replace_external(&mut self, value: T) -> T {
-        core::mem::replace(self.get_mut_external(), value)
+        self.modify_external(|current| core::mem::replace(current, value))
     }
 
     /// Takes the wrapped value, leaving `Default::default()` in its place,
@@ -137,7 +143,7 @@ impl<'ctx, T, O: ?Sized> WatchedCore<'ctx, T, O> {
     where
         T: Default,
     {
-        core::mem::take(self.get_mut(ctx))
+        self.modify(ctx, core::mem::take)
     }
 
     #[cfg_attr(do_cycle_debug, track_caller)]
@@ -145,7 +151,65 @@ impl<'ctx, T, O: ?Sized> WatchedCore<'ctx, T, O> {
     where
         T: Default,
     {
-        core::mem::take(self.get_mut_external())
+        self.modify_external(core::mem::take)
+    }
+
+    /// Change the wrapped value in place, and notify watchers that the
+    /// value has changed.
+    ///
+    /// Unlike [`get_mut`](Self::get_mut), the change is finished before
+    /// watchers are notified, so watch functions which used
+    /// [`select`](Self::select) are only re-run if what they selected has
+    /// changed.
+    #[cfg_attr(do_cycle_debug, track_caller)]
+    pub fn modify<F, R>(&mut self, ctx: WatchArg<'_, 'ctx, O>, func: F) -> R
+    where
+        F: FnOnce(&mut T) -> R,
+    {
+        let ret = func(&mut self.value);
+        self.changed(Some(ctx), true);
+        self.meta.watched(ctx);
+        ret
+    }
+
+    #[cfg_attr(do_cycle_debug, track_caller)]
+    pub fn modify_external<F, R>(&mut self, func: F) -> R
+    where
+        F: FnOnce(&mut T) -> R,
+    {
+        let ret = func(&mut self.value);
+        self.changed(None, true);
+        ret
+    }
+
+    /// Get part of the wrapped value, binding a watch closure so that it
+    /// is only re-run when that part changes.
+    ///
+    /// This is only known when the value is changed with a method which
+    /// finishes the change before notifying watchers, such as
+    /// [`modify`](Self::modify), [`replace`](Self::replace) or
+    /// [`set_if_neq`](Self::set_if_neq).  After a change through
+    /// [`get_mut`](Self::get_mut), the watch closure is re-run
+    /// regardless.
+    pub fn select<F, V>(&self, ctx: WatchArg<'_, 'ctx, O>, func: F) -> V
+    where
+        T: 'ctx,
+        F: 'ctx + Fn(&T) -> V,
+        V: 'ctx + Clone + PartialEq,
+    {
+        let selected = func(&self.value);
+        let previous = selected.clone();
+        let memo = Memo {
+            watchers: WatchSet::new(),
+            changed: Box::new(move |value| func(value) != previous),
+        };
+        memo.watchers.watched(ctx);
+        self.meta.with_extra_for(ctx, |memos| {
+            // forget what was selected by runs which are over
+            memos.0.retain(|memo| memo.watchers.has_fresh());
+            memos.0.push(memo);
+        });
+        selected
     }
 
     /// This function provides a way to set a value for a watched value
@@ -158,7 +222,7 @@ impl<'ctx, T, O: ?Sized> WatchedCore<'ctx, T, O> {
     {
         if self.value != value {
             self.value = value;
-            self.meta.trigger(ctx);
+            self.changed(Some(ctx), true);
         }
     }
 
@@ -169,9 +233,118 @@ impl<'ctx, T, O: ?Sized> WatchedCore<'ctx, T, O> {
     {
         if self.value != value {
             self.value = value;
-            self.meta.trigger_external();
+            self.changed(None, true);
+        }
+    }
+
+    /// Set the wrapped value, only notifying watchers if the hash of the
+    /// new value differs from the hash of the old one.  This is an
+    /// alternative to [`set_if_neq`](Self::set_if_neq) for types which are
+    /// not `PartialEq`, or are expensive to compare.
+    #[cfg_attr(do_cycle_debug, track_caller)]
+    pub fn set_if_hash_neq(&mut self, value: T, ctx: WatchArg<'_, 'ctx, O>)
+    where
+        T: Hash,
+    {
+        if hash_value(&self.value) != hash_value(&value) {
+            self.value = value;
+            self.changed(Some(ctx), true);
+        }
+    }
+
+    #[cfg_attr(do_cycle_debug, track_caller)]
+    pub fn set_if_hash_neq_external(&mut self, value: T)
+    where
+        T: Hash,
+    {
+        if hash_value(&self.value) != hash_value(&value) {
+            self.value = value;
+            self.changed(None, true);
         }
     }
+
+    /// Change the wrapped value in place, like [`modify`](Self::modify),
+    /// but only notify watchers if its hash changed.
+    #[cfg_attr(do_cycle_debug, track_caller)]
+    pub fn modify_if_hash_changed<F, R>(
+        &mut self,
+        ctx: WatchArg<'_, 'ctx, O>,
+        func: F,
+    ) -> R
+    where
+        T: Hash,
+        F: FnOnce(&mut T) -> R,
+    {
+        self.modify_if_fingerprint_changed(ctx, hash_value, func)
+    }
+
+    #[cfg_attr(do_cycle_debug, track_caller)]
+    pub fn modify_if_hash_changed_external<F, R>(&mut self, func: F) -> R
+    where
+        T: Hash,
+        F: FnOnce(&mut T) -> R,
+    {
+        self.modify_if_fingerprint_changed_external(hash_value, func)
+    }
+
+    /// Change the wrapped value in place, like [`modify`](Self::modify),
+    /// but only notify watchers if the result of `fingerprint` is
+    /// different afterwards than it was before.
+    #[cfg_attr(do_cycle_debug, track_caller)]
+    pub fn modify_if_fingerprint_changed<P, K, F, R>(
+        &mut self,
+        ctx: WatchArg<'_, 'ctx, O>,
+        fingerprint: P,
+        func: F,
+    ) -> R
+    where
+        P: Fn(&T) -> K,
+        K: PartialEq,
+        F: FnOnce(&mut T) -> R,
+    {
+        let (ret, changed) = self.modify_fingerprinted(fingerprint, func);
+        if changed {
+            self.changed(Some(ctx), true);
+        }
+        self.meta.watched(ctx);
+        ret
+    }
+
+    #[cfg_attr(do_cycle_debug, track_caller)]
+    pub fn modify_if_fingerprint_changed_external<P, K, F, R>(
+        &mut self,
+        fingerprint: P,
+        func: F,
+    ) -> R
+    where
+        P: Fn(&T) -> K,
+        K: PartialEq,
+        F: FnOnce(&mut T) -> R,
+    {
+        let (ret, changed) = self.modify_fingerprinted(fingerprint, func);
+        if changed {
+            self.changed(None, true);
+        }
+        ret
+    }
+
+    /// Change the wrapped value in place, returning whether the result of
+    /// `fingerprint` changed.  Watchers are left for the caller to notify.
+    fn modify_fingerprinted<P, K, F, R>(
+        &mut self,
+        fingerprint: P,
+        func: F,
+    ) -> (R, bool)
+    where
+        P: Fn(&T) -> K,
+        K: PartialEq,
+        F: FnOnce(&mut T) -> R,
+    {
+        let before = fingerprint(&self.value);
+        let ret = func(&mut self.value);
+        let changed = fingerprint(&self.value) != before;
+        (ret, changed)
+    }
 }
 
 impl<'ctx, T: ?Sized, O: ?Sized> WatchedCore<'ctx, T, O> {
@@ -185,14 +358,14 @@ impl<'ctx, T: ?Sized, O: ?Sized> WatchedCore<'ctx, T, O> {
     /// watchers that the value has changed.
     #[cfg_attr(do_cycle_debug, track_caller)]
     pub fn get_mut(&mut self, ctx: WatchArg<'_, 'ctx, O>) -> &mut T {
-        self.meta.trigger(ctx);
+        self.changed(Some(ctx), false);
         self.meta.watched(ctx);
         &mut self.value
     }
 
     #[cfg_attr(do_cycle_debug, track_caller)]
     pub fn get_mut_external(&mut self) -> &mut T {
-        self.meta.trigger_external();
+        self.changed(None, false);
         &mut self.value
     }
 
@@ -201,6 +374,36 @@ impl<'ctx, T: ?Sized, O: ?Sized> WatchedCore<'ctx, T, O> {
     pub fn get_unwatched(&self) -> &T {
         &self.value
     }
+
+    /// Notify watchers that the value has changed.  If the change is
+    /// `finished`, watch functions which used [`select`](Self::select) are
+    /// only notified if what they selected has changed.
+    #[cfg_attr(do_cycle_debug, track_caller)]
+    fn changed(&mut self, ctx: Option<WatchArg<'_, 'ctx, O>>, finished: bool) {
+        let memos = self.meta.with_extra(core::mem::take).unwrap_or_default();
+        let mut unchanged = Vec::new();
+        for memo in memos.0 {
+            if !memo.watchers.has_fresh() {
+                continue;
+            }
+            if finished && !(memo.changed)(&self.value) {
+                unchanged.push(memo);
+                continue;
+            }
+            match ctx {
+                Some(ctx) => memo.watchers.trigger(ctx),
+                None => memo.watchers.trigger_external(),
+            }
+        }
+        if !unchanged.is_empty() {
+            self.meta.with_extra(|memos| memos.0 = unchanged);
+        }
+        // the head of the set is kept while it still holds memos
+        match ctx {
+            Some(ctx) => self.meta.trigger(ctx),
+            None => self.meta.trigger_external(),
+        }
+    }
 }
 
 #[cfg(feature = "std")]
@@ -212,17 +415,39 @@ impl<T: ?Sized> WatchedCore<'static, T, DefaultOwner> {
 
     #[cfg_attr(do_cycle_debug, track_caller)]
     pub fn get_mut_auto(&mut self) -> &mut T {
-        self.meta.trigger_auto();
+        self.changed_auto(false);
         self.meta.watched_auto();
         &mut self.value
     }
 
+    #[cfg_attr(do_cycle_debug, track_caller)]
+    fn changed_auto(&mut self, finished: bool) {
+        let found_current = WatchArg::try_with_current(|arg| {
+            self.changed(Some(arg), finished);
+        });
+        if found_current.is_none() {
+            self.changed(None, finished);
+        }
+    }
+
+    /// Set the wrapped value, notifying watchers without binding the
+    /// current watch closure.
+    #[cfg(feature = "serde")]
+    #[cfg_attr(do_cycle_debug, track_caller)]
+    pub(crate) fn set_auto(&mut self, value: T)
+    where
+        T: Sized,
+    {
+        self.value = value;
+        self.changed_auto(true);
+    }
+
     #[cfg_attr(do_cycle_debug, track_caller)]
     pub fn replace_auto(&mut self, value: T) -> T
     where
         T: Sized,
     {
-        core::mem::replace(self.get_mut_auto(), value)
+        self.modify_auto(|current| core::mem::replace(current, value))
     }
 
     #[cfg_attr(do_cycle_debug, track_caller)]
@@ -230,7 +455,33 @@ impl<T: ?Sized> WatchedCore<'static, T, DefaultOwner> {
     where
         T: Default,
     {
-        core::mem::take(self.get_mut_auto())
+        self.modify_auto(core::mem::take)
+    }
+
+    #[cfg_attr(do_cycle_debug, track_caller)]
+    pub fn modify_auto<F, R>(&mut self, func: F) -> R
+    where
+        T: Sized,
+        F: FnOnce(&mut T) -> R,
+    {
+        let ret = func(&mut self.value);
+        self.changed_auto(true);
+        self.meta.watched_auto();
+        ret
+    }
+
+    pub fn select_auto<F, V>(&self, func: F) -> V
+    where
+        T: 'static + Sized,
+        F: 'static + Fn(&T) -> V,
+        V: 'static + Clone + PartialEq,
+    {
+        let mut func = Some(func);
+        let mut selected = None;
+        WatchArg::try_with_current(|arg| {
+            selected = Some(self.select(arg, func.take().unwrap()));
+        });
+        selected.unwrap_or_else(|| func.unwrap()(&self.value))
     }
 
     /// This function provides a way to set a value for a watched value
@@ -243,8 +494,48 @@ impl<T: ?Sized> WatchedCore<'static, T, DefaultOwner> {
     {
         if self.value != value {
             self.value = value;
-            self.meta.trigger_auto();
+            self.changed_auto(true);
+        }
+    }
+
+    #[cfg_attr(do_cycle_debug, track_caller)]
+    pub fn set_if_hash_neq_auto(&mut self, value: T)
+    where
+        T: Hash + Sized,
+    {
+        if hash_value(&self.value) != hash_value(&value) {
+            self.value = value;
+            self.changed_auto(true);
+        }
+    }
+
+    #[cfg_attr(do_cycle_debug, track_caller)]
+    pub fn modify_if_hash_changed_auto<F, R>(&mut self, func: F) -> R
+    where
+        T: Hash + Sized,
+        F: FnOnce(&mut T) -> R,
+    {
+        self.modify_if_fingerprint_changed_auto(hash_value, func)
+    }
+
+    #[cfg_attr(do_cycle_debug, track_caller)]
+    pub fn modify_if_fingerprint_changed_auto<P, K, F, R>(
+        &mut self,
+        fingerprint: P,
+        func: F,
+    ) -> R
+    where
+        T: Sized,
+        P: Fn(&T) -> K,
+        K: PartialEq,
+        F: FnOnce(&mut T) -> R,
+    {
+        let (ret, changed) = self.modify_fingerprinted(fingerprint, func);
+        if changed {
+            self.changed_auto(true);
         }
+        self.meta.watched_auto();
+        ret
     }
 }
 
@@ -452,6 +743,87 @@ impl<T: ?Sized> WatchedCellCore<'static, T, DefaultOwner> {
     }
 }
 
+#[cfg(feature = "serde")]
+mod serde_impls {
+    use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+    use super::{WatchedCellCore, WatchedCore};
+
+    /// Serializes the value without binding any watch closure.
+    impl<'ctx, T, O> Serialize for WatchedCore<'ctx, T, O>
+    where
+        T: ?Sized + Serialize,
+        O: ?Sized,
+    {
+        fn serialize<S: Serializer>(
+            &self,
+            serializer: S,
+        ) -> Result<S::Ok, S::Error> {
+            self.get_unwatched().serialize(serializer)
+        }
+    }
+
+    /// Deserializing in place triggers any watch closures bound to the
+    /// value, as though it were set externally.  If deserializing fails,
+    /// the value is left unchanged.
+    impl<'de, 'ctx, T, O> Deserialize<'de> for WatchedCore<'ctx, T, O>
+    where
+        T: Deserialize<'de>,
+        O: ?Sized,
+    {
+        fn deserialize<D: Deserializer<'de>>(
+            deserializer: D,
+        ) -> Result<Self, D::Error> {
+            T::deserialize(deserializer).map(Self::new)
+        }
+
+        fn deserialize_in_place<D: Deserializer<'de>>(
+            deserializer: D,
+            place: &mut Self,
+        ) -> Result<(), D::Error> {
+            place.replace_external(T::deserialize(deserializer)?);
+            Ok(())
+        }
+    }
+
+    /// Serializes the value without binding any watch closure.
+    impl<'ctx, T, O> Serialize for WatchedCellCore<'ctx, T, O>
+    where
+        T: Copy + Serialize,
+        O: ?Sized,
+    {
+        fn serialize<S: Serializer>(
+            &self,
+            serializer: S,
+        ) -> Result<S::Ok, S::Error> {
+            self.get_unwatched().serialize(serializer)
+        }
+    }
+
+    /// Deserializing in place triggers any watch closures bound to the
+    /// value, as though it were set externally.  If deserializing fails,
+    /// the value is left unchanged.
+    impl<'de, 'ctx, T, O> Deserialize<'de> for WatchedCellCore<'ctx, T, O>
+    where
+        T: Deserialize<'de>,
+        O: ?Sized,
+    {
+        fn deserialize<D: Deserializer<'de>>(
+            deserializer: D,
+        ) -> Result<Self, D::Error> {
+            T::deserialize(deserializer).map(Self::new)
+        }
+
+        fn deserialize_in_place<D: Deserializer<'de>>(
+            deserializer: D,
+            place: &mut Self,
+        ) -> Result<(), D::Error> {
+            place.set_external(T::deserialize(deserializer)?);
+            Ok(())
+        }
+    }
+}
+
 pub trait WatchedValueCore<'ctx, O: ?Sized> {
     type Value;
 
@@ -468,6 +840,37 @@ pub trait WatchedValueCore<'ctx, O: ?Sized> {
         }
     }
 
+    /// Combine this value with another, producing both values as a tuple.
+    fn zip<W>(
+        self,
+        other: W,
+    ) -> impl WatchedValueCore<'ctx, O, Value = (Self::Value, W::Value)>
+    where
+        Self: Sized,
+        W: WatchedValueCore<'ctx, O>,
+    {
+        (self, other)
+    }
+
+    /// Use this value to select another watched value to read from.
+    ///
+    /// The selected source is chosen again each time the value is read, so
+    /// a watch closure only depends on whichever source was selected last.
+    fn and_then<F, W>(
+        self,
+        select_fn: F,
+    ) -> impl WatchedValueCore<'ctx, O, Value = W::Value>
+    where
+        Self: Sized,
+        F: FnOnce(Self::Value) -> W,
+        W: WatchedValueCore<'ctx, O>,
+    {
+        AndThenWatchedValue {
+            source: self,
+            select_fn,
+        }
+    }
+
     /// implementation detail so that Box<dyn WatchedValueCore> can work
     #[doc(hidden)]
     fn get_boxed(
@@ -554,3 +957,93 @@ where
         (self.map_fn)(self.source.get_unwatched())
     }
 }
+
+#[derive(Clone, Copy, Debug)]
+struct AndThenWatchedValue<V, F> {
+    source: V,
+    select_fn: F,
+}
+
+impl<'ctx, O, T, V, F, W> WatchedValueCore<'ctx, O>
+    for AndThenWatchedValue<V, F>
+where
+    O: ?Sized,
+    V: WatchedValueCore<'ctx, O, Value = T>,
+    F: FnOnce(T) -> W,
+    W: WatchedValueCore<'ctx, O>,
+{
+    type Value = W::Value;
+
+    fn get(self, ctx: WatchArg<'_, 'ctx, O>) -> Self::Value {
+        (self.select_fn)(self.source.get(ctx)).get(ctx)
+    }
+
+    fn get_unwatched(self) -> Self::Value {
+        (self.select_fn)(self.source.get_unwatched()).get_unwatched()
+    }
+}
+
+impl<'ctx, O, V> WatchedValueCore<'ctx, O> for Option<V>
+where
+    O: ?Sized,
+    V: WatchedValueCore<'ctx, O>,
+{
+    type Value = Option<V::Value>;
+
+    fn get(self, ctx: WatchArg<'_, 'ctx, O>) -> Self::Value {
+        self.map(|source| source.get(ctx))
+    }
+
+    fn get_unwatched(self) -> Self::Value {
+        self.map(V::get_unwatched)
+    }
+}
+
+impl<'ctx, O, V, const N: usize> WatchedValueCore<'ctx, O> for [V; N]
+where
+    O: ?Sized,
+    V: WatchedValueCore<'ctx, O>,
+{
+    type Value = [V::Value; N];
+
+    fn get(self, ctx: WatchArg<'_, 'ctx, O>) -> Self::Value {
+        self.map(|source| source.get(ctx))
+    }
+
+    fn get_unwatched(self) -> Self::Value {
+        self.map(V::get_unwatched)
+    }
+}
+
+macro_rules! tuple_watched_value {
+    ($($name:ident)+) => {
+        impl<'ctx, O, $($name),+> WatchedValueCore<'ctx, O> for ($($name,)+)
+        where
+            O: ?Sized,
+            $($name: WatchedValueCore<'ctx, O>,)+
+        {
+            type Value = ($($name::Value,)+);
+
+            #[allow(non_snake_case)]
+            fn get(self, ctx: WatchArg<'_, 'ctx, O>) -> Self::Value {
+                let ($($name,)+) = self;
+                ($($name.get(ctx),)+)
+            }
+
+            #[allow(non_snake_case)]
+            fn get_unwatched(self) -> Self::Value {
+                let ($($name,)+) = self;
+                ($($name.get_unwatched(),)+)
+            }
+        }
+    };
+}
+
+tuple_watched_value!(A);
+tuple_watched_value!(A B);
+tuple_watched_value!(A B C);
+tuple_watched_value!(A B C D);
+tuple_watched_value!(A B C D E);
+tuple_watched_value!(A B C D E F);
+tuple_watched_value!(A B C D E F G);
+tuple_watched_value!(A B C D E F G H);
@@ -1,9 +1,20 @@
 /* SPDX-License-Identifier: (Apache-2.0 OR MIT OR Zlib) */
 /* Copyright © 2021 Violet Leonard */
 
-use {alloc::rc::Weak, core::cell::RefCell};
+use {
+    alloc::rc::Weak,
+    core::{
+        cell::{Cell, RefCell},
+        future::Future,
+        time::Duration,
+    },
+};
 
-use crate::{DefaultOwner, WatchArg, WatchContext, WatchName};
+use crate::{
+    debounce::{DebounceGate, ThrottleGate},
+    watch_async::spawn_async_watch,
+    DefaultOwner, WatchArg, WatchContext, WatchName, WatchedCore,
+};
 
 pub trait Watcher<'ctx, O: ?Sized = DefaultOwner> {
     fn init(init: impl WatcherInit<'ctx, Self, O>);
@@ -36,6 +47,114 @@ pub trait WatcherInit<'ctx, T: ?Sized, O: ?Sized = DefaultOwner> {
         W: 'static + WatcherHolder<'static, DefaultOwner>,
         W::Content: Watcher<'static, DefaultOwner>;
 
+    /// Use this to set up a function which starts some asynchronous work
+    /// whenever watched values referenced inside change.
+    ///
+    /// The future returned by the function is polled by the context itself,
+    /// being woken in the same way as a [`SyncTrigger`](crate::SyncTrigger),
+    /// so it does not depend on any particular executor.  When it completes,
+    /// its output is called with the watcher, on the context's thread.  If
+    /// the function is re-run before that, the previous future is dropped.
+    ///
+    /// Only the function is watched, not the future.
+    #[cfg(feature = "std")]
+    fn watch_async<F, Fut>(&mut self, func: F)
+    where
+        Self: WatcherInit<'static, T, DefaultOwner>,
+        F: 'static + Fn(&mut T) -> Fut,
+        Fut: 'static + Future,
+        Fut::Output: FnOnce(&mut T);
+
+    /// Like [`watch`](Self::watch), but once the function has run, it is
+    /// re-run only after `delay` has passed without any watched value it
+    /// referenced changing, according to the clock of the context.
+    ///
+    /// Each change made while waiting restarts the delay.  To debounce a
+    /// single value instead, see [`Debounced`](crate::Debounced).
+    #[cfg(feature = "std")]
+    fn watch_debounced<F>(&mut self, delay: Duration, func: F)
+    where
+        Self: WatcherInit<'static, T, DefaultOwner>,
+        F: 'static + Fn(&mut T);
+
+    /// Like [`watch`](Self::watch), but the function is re-run at most once
+    /// per `period`, according to the clock of the context.  Changes which
+    /// come sooner are handled once the period is over.
+    #[cfg(feature = "std")]
+    fn watch_throttled<F>(&mut self, period: Duration, func: F)
+    where
+        Self: WatcherInit<'static, T, DefaultOwner>,
+        F: 'static + Fn(&mut T);
+
+    /// Use this to set up a function which is called with the old and new
+    /// values whenever the value chosen by `select` changes.
+    ///
+    /// `select` is watched, and a copy of the value it returns is kept to
+    /// compare against the next time it is re-run.  The handler is only
+    /// called if the value is no longer equal, and is not watched itself.
+    #[cfg(feature = "std")]
+    fn on_change<S, V, F>(&mut self, select: S, handler: F)
+    where
+        Self: WatcherInit<'static, T, DefaultOwner>,
+        S: 'static + Fn(&T) -> &V,
+        V: 'static + Clone + PartialEq,
+        F: 'static + Fn(&mut T, &V, &V);
+
+    /// Use this to keep a watched value set to the result of `expr`, which
+    /// is re-run whenever watched values referenced inside change.
+    ///
+    /// The target is only set if the result is different from the value it
+    /// already holds, so watch functions which use it are not re-run
+    /// needlessly.
+    #[cfg(feature = "std")]
+    fn bind<G, V, F>(&mut self, target: G, expr: F)
+    where
+        Self: WatcherInit<'static, T, DefaultOwner>,
+        G: 'static + Fn(&mut T) -> &mut crate::Watched<V>,
+        V: 'static + PartialEq,
+        F: 'static + Fn(&T) -> V;
+
+    /// Keep two watched values in sync, converting each to the other with
+    /// `to_b` and `to_a` whenever one of them changes.
+    ///
+    /// When the binding is set up, or if both values changed since it last
+    /// ran, `a` is kept and `b` is overwritten.  A value is only set if the
+    /// conversion produced something different from what it already holds,
+    /// and the values the binding wrote are remembered, so it is never
+    /// re-run by its own changes, even if converting back and forth does not
+    /// give the original value.
+    #[cfg(feature = "std")]
+    fn bind_two_way<GA, GB, A, B, FB, FA>(
+        &mut self,
+        a: GA,
+        b: GB,
+        to_b: FB,
+        to_a: FA,
+    ) where
+        Self: WatcherInit<'static, T, DefaultOwner>,
+        GA: 'static + Fn(&mut T) -> &mut crate::Watched<A>,
+        GB: 'static + Fn(&mut T) -> &mut crate::Watched<B>,
+        A: 'static + Clone + PartialEq,
+        B: 'static + Clone + PartialEq,
+        FB: 'static + Fn(&A) -> B,
+        FA: 'static + Fn(&B) -> A;
+
+    /// Use this to load a [`WatchedResource`](crate::WatchedResource) in
+    /// the background.
+    ///
+    /// `source` is watched, and returns the loader to run.  Whenever the
+    /// watched values it referenced change, it is re-run, and its new
+    /// loader supersedes any load which has not finished yet.
+    #[cfg(feature = "std")]
+    fn load_resource<G, S, L, R, E>(&mut self, target: G, source: S)
+    where
+        Self: WatcherInit<'static, T, DefaultOwner>,
+        G: 'static + Fn(&mut T) -> &mut crate::WatchedResource<R, E>,
+        S: 'static + Fn(&T) -> L,
+        L: 'static + Send + FnOnce() -> Result<R, E>,
+        R: 'static + Send,
+        E: 'static + Send;
+
     /// Use this to set up a function which should be re-run whenever watched
     /// values referenced inside change.
     fn watch_explicit<F>(&mut self, func: F)
@@ -47,6 +166,65 @@ pub trait WatcherInit<'ctx, T: ?Sized, O: ?Sized = DefaultOwner> {
         F: 'static + Fn(WatchArg<'_, 'ctx, O>, &mut T) -> Option<W>,
         W: 'ctx + WatcherHolder<'ctx, O>,
         W::Content: Watcher<'ctx, O>;
+
+    /// Use this to set up a function which starts some asynchronous work
+    /// whenever watched values referenced inside change.  See
+    /// [`WatcherInit::watch_async`].
+    fn watch_async_explicit<F, Fut>(&mut self, func: F)
+    where
+        F: 'static + Fn(WatchArg<'_, 'ctx, O>, &mut T) -> Fut,
+        Fut: 'ctx + Future,
+        Fut::Output: FnOnce(&mut T);
+
+    /// See [`WatcherInit::watch_debounced`].
+    fn watch_debounced_explicit<F>(&mut self, delay: Duration, func: F)
+    where
+        F: 'static + Fn(WatchArg<'_, 'ctx, O>, &mut T);
+
+    /// See [`WatcherInit::watch_throttled`].
+    fn watch_throttled_explicit<F>(&mut self, period: Duration, func: F)
+    where
+        F: 'static + Fn(WatchArg<'_, 'ctx, O>, &mut T);
+
+    /// See [`WatcherInit::on_change`].
+    fn on_change_explicit<S, V, F>(&mut self, select: S, handler: F)
+    where
+        S: 'static + for<'v> Fn(WatchArg<'_, 'ctx, O>, &'v T) -> &'v V,
+        V: 'static + Clone + PartialEq,
+        F: 'static + Fn(&mut T, &V, &V);
+
+    /// See [`WatcherInit::load_resource`].
+    #[cfg(feature = "std")]
+    fn load_resource_explicit<G, S, L, R, E>(&mut self, target: G, source: S)
+    where
+        G: 'static + Fn(&mut T) -> &mut crate::WatchedResource<R, E>,
+        S: 'static + Fn(WatchArg<'_, 'ctx, O>, &T) -> L,
+        L: 'static + Send + FnOnce() -> Result<R, E>,
+        R: 'static + Send,
+        E: 'static + Send;
+
+    /// See [`WatcherInit::bind`].
+    fn bind_explicit<G, V, F>(&mut self, target: G, expr: F)
+    where
+        G: 'static + Fn(&mut T) -> &mut WatchedCore<'ctx, V, O>,
+        V: 'static + PartialEq,
+        F: 'static + Fn(WatchArg<'_, 'ctx, O>, &T) -> V;
+
+    /// See [`WatcherInit::bind_two_way`].  This binds two
+    /// [`WatchedCore`] values directly.
+    fn bind_two_way_core<GA, GB, A, B, FB, FA>(
+        &mut self,
+        a: GA,
+        b: GB,
+        to_b: FB,
+        to_a: FA,
+    ) where
+        GA: 'static + Fn(&mut T) -> &mut WatchedCore<'ctx, A, O>,
+        GB: 'static + Fn(&mut T) -> &mut WatchedCore<'ctx, B, O>,
+        A: 'static + Clone + PartialEq,
+        B: 'static + Clone + PartialEq,
+        FB: 'static + Fn(&A) -> B,
+        FA: 'static + Fn(&B) -> A;
 }
 
 pub trait WatcherHolder<'ctx, O: ?Sized>: Clone {
@@ -72,6 +250,66 @@ where
     }
 }
 
+#[cfg(feature = "std")]
+impl<'ctx, T, O> WatcherHolder<'ctx, O>
+    for std::sync::Weak<std::sync::Mutex<T>>
+where
+    T: ?Sized + Watcher<'ctx, O>,
+    O: ?Sized,
+{
+    type Content = T;
+
+    fn get_mut<F, R>(&self, _owner: &mut O, f: F) -> Option<R>
+    where
+        F: FnOnce(&mut Self::Content) -> R,
+    {
+        let strong = self.upgrade()?;
+        let mut guard = strong
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner);
+        Some(f(&mut *guard))
+    }
+}
+
+/// A [`WatcherHolder`] which finds its watcher inside the owner of the
+/// watch context, rather than behind a shared pointer.
+///
+/// This is useful when the owner is responsible for all the content of the
+/// context, such as when the context is built on another thread.
+#[derive(Clone, Copy, Debug)]
+pub struct OwnerPath<F> {
+    path: F,
+}
+
+impl<F> OwnerPath<F> {
+    /// Create a new `OwnerPath` from a function which borrows the watcher
+    /// from the owner.
+    pub fn new<O, T>(path: F) -> Self
+    where
+        O: ?Sized,
+        T: ?Sized,
+        F: Clone + Fn(&mut O) -> &mut T,
+    {
+        Self { path }
+    }
+}
+
+impl<'ctx, T, O, F> WatcherHolder<'ctx, O> for OwnerPath<F>
+where
+    T: ?Sized,
+    O: ?Sized,
+    F: Clone + Fn(&mut O) -> &mut T,
+{
+    type Content = T;
+
+    fn get_mut<G, R>(&self, owner: &mut O, f: G) -> Option<R>
+    where
+        G: FnOnce(&mut Self::Content) -> R,
+    {
+        Some(f((self.path)(owner)))
+    }
+}
+
 pub(crate) fn init_watcher<'ctx, T, O>(
     ctx: &mut WatchContext<'ctx, O>,
     holder: &T,
@@ -158,6 +396,115 @@ where
         });
     }
 
+    #[cfg(feature = "std")]
+    #[cfg_attr(do_cycle_debug, track_caller)]
+    fn watch_async<F, Fut>(&mut self, func: F)
+    where
+        Self: WatcherInit<'static, Content, DefaultOwner>,
+        F: 'static + Fn(&mut Content) -> Fut,
+        Fut: 'static + Future,
+        Fut::Output: FnOnce(&mut Content),
+    {
+        self.watch_async_explicit(move |arg, content| {
+            arg.use_as_current(|| func(content))
+        });
+    }
+
+    #[cfg(feature = "std")]
+    #[cfg_attr(do_cycle_debug, track_caller)]
+    fn watch_debounced<F>(&mut self, delay: Duration, func: F)
+    where
+        Self: WatcherInit<'static, Content, DefaultOwner>,
+        F: 'static + Fn(&mut Content),
+    {
+        self.watch_debounced_explicit(delay, move |arg, content| {
+            arg.use_as_current(|| func(content));
+        });
+    }
+
+    #[cfg(feature = "std")]
+    #[cfg_attr(do_cycle_debug, track_caller)]
+    fn watch_throttled<F>(&mut self, period: Duration, func: F)
+    where
+        Self: WatcherInit<'static, Content, DefaultOwner>,
+        F: 'static + Fn(&mut Content),
+    {
+        self.watch_throttled_explicit(period, move |arg, content| {
+            arg.use_as_current(|| func(content));
+        });
+    }
+
+    #[cfg(feature = "std")]
+    #[cfg_attr(do_cycle_debug, track_caller)]
+    fn on_change<S, V, F>(&mut self, select: S, handler: F)
+    where
+        Self: WatcherInit<'static, Content, DefaultOwner>,
+        S: 'static + Fn(&Content) -> &V,
+        V: 'static + Clone + PartialEq,
+        F: 'static + Fn(&mut Content, &V, &V),
+    {
+        self.on_change_explicit(
+            move |arg, content| arg.use_as_current(|| select(content)),
+            handler,
+        );
+    }
+
+    #[cfg(feature = "std")]
+    #[cfg_attr(do_cycle_debug, track_caller)]
+    fn load_resource<G, S, L, R, E>(&mut self, target: G, source: S)
+    where
+        Self: WatcherInit<'static, Content, DefaultOwner>,
+        G: 'static + Fn(&mut Content) -> &mut crate::WatchedResource<R, E>,
+        S: 'static + Fn(&Content) -> L,
+        L: 'static + Send + FnOnce() -> Result<R, E>,
+        R: 'static + Send,
+        E: 'static + Send,
+    {
+        self.load_resource_explicit(target, move |arg, content| {
+            arg.use_as_current(|| source(content))
+        });
+    }
+
+    #[cfg(feature = "std")]
+    #[cfg_attr(do_cycle_debug, track_caller)]
+    fn bind<G, V, F>(&mut self, target: G, expr: F)
+    where
+        Self: WatcherInit<'static, Content, DefaultOwner>,
+        G: 'static + Fn(&mut Content) -> &mut crate::Watched<V>,
+        V: 'static + PartialEq,
+        F: 'static + Fn(&Content) -> V,
+    {
+        self.bind_explicit(
+            move |content| crate::Watched::core_mut(target(content)),
+            move |arg, content| arg.use_as_current(|| expr(content)),
+        );
+    }
+
+    #[cfg(feature = "std")]
+    #[cfg_attr(do_cycle_debug, track_caller)]
+    fn bind_two_way<GA, GB, A, B, FB, FA>(
+        &mut self,
+        a: GA,
+        b: GB,
+        to_b: FB,
+        to_a: FA,
+    ) where
+        Self: WatcherInit<'static, Content, DefaultOwner>,
+        GA: 'static + Fn(&mut Content) -> &mut crate::Watched<A>,
+        GB: 'static + Fn(&mut Content) -> &mut crate::Watched<B>,
+        A: 'static + Clone + PartialEq,
+        B: 'static + Clone + PartialEq,
+        FB: 'static + Fn(&A) -> B,
+        FA: 'static + Fn(&B) -> A,
+    {
+        self.bind_two_way_core(
+            move |content| crate::Watched::core_mut(a(content)),
+            move |content| crate::Watched::core_mut(b(content)),
+            to_b,
+            to_a,
+        );
+    }
+
     #[cfg_attr(do_cycle_debug, track_caller)]
     fn watch_explicit<F>(&mut self, func: F)
     where
@@ -192,4 +539,139 @@ where
             }
         });
     }
+
+    #[cfg_attr(do_cycle_debug, track_caller)]
+    fn watch_async_explicit<F, Fut>(&mut self, func: F)
+    where
+        F: 'static + Fn(WatchArg<'_, 'ctx, Owner>, &mut Content) -> Fut,
+        Fut: 'ctx + Future,
+        Fut::Output: FnOnce(&mut Content),
+    {
+        let debug_name = WatchName::from_caller();
+        spawn_async_watch(self.ctx, debug_name, self.path, func);
+    }
+
+    #[cfg_attr(do_cycle_debug, track_caller)]
+    fn watch_debounced_explicit<F>(&mut self, delay: Duration, func: F)
+    where
+        F: 'static + Fn(WatchArg<'_, 'ctx, Owner>, &mut Content),
+    {
+        let gate = DebounceGate::new(delay);
+        self.watch_explicit(move |arg, content| {
+            if gate.should_run(arg) {
+                func(arg, content);
+            }
+        });
+    }
+
+    #[cfg_attr(do_cycle_debug, track_caller)]
+    fn watch_throttled_explicit<F>(&mut self, period: Duration, func: F)
+    where
+        F: 'static + Fn(WatchArg<'_, 'ctx, Owner>, &mut Content),
+    {
+        let gate = ThrottleGate::new(period);
+        self.watch_explicit(move |arg, content| {
+            if gate.should_run(arg) {
+                func(arg, content);
+            }
+        });
+    }
+
+    #[cfg_attr(do_cycle_debug, track_caller)]
+    fn on_change_explicit<S, V, F>(&mut self, select: S, handler: F)
+    where
+        S: 'static
+            + for<'v> Fn(WatchArg<'_, 'ctx, Owner>, &'v Content) -> &'v V,
+        V: 'static + Clone + PartialEq,
+        F: 'static + Fn(&mut Content, &V, &V),
+    {
+        let last = Cell::new(None);
+        self.watch_explicit(move |arg, content| {
+            let value = select(arg, content);
+            match last.take() {
+                Some(old) if old != *value => {
+                    let new = value.clone();
+                    handler(content, &old, &new);
+                    last.set(Some(new));
+                }
+                Some(old) => last.set(Some(old)),
+                None => last.set(Some(value.clone())),
+            }
+        });
+    }
+
+    #[cfg(feature = "std")]
+    #[cfg_attr(do_cycle_debug, track_caller)]
+    fn load_resource_explicit<G, S, L, R, E>(&mut self, target: G, source: S)
+    where
+        G: 'static + Fn(&mut Content) -> &mut crate::WatchedResource<R, E>,
+        S: 'static + Fn(WatchArg<'_, 'ctx, Owner>, &Content) -> L,
+        L: 'static + Send + FnOnce() -> Result<R, E>,
+        R: 'static + Send,
+        E: 'static + Send,
+    {
+        self.watch_explicit(move |arg, content| {
+            let loader = source(arg, content);
+            target(content).fetch(loader, arg);
+        });
+    }
+
+    #[cfg_attr(do_cycle_debug, track_caller)]
+    fn bind_explicit<G, V, F>(&mut self, target: G, expr: F)
+    where
+        G: 'static + Fn(&mut Content) -> &mut WatchedCore<'ctx, V, Owner>,
+        V: 'static + PartialEq,
+        F: 'static + Fn(WatchArg<'_, 'ctx, Owner>, &Content) -> V,
+    {
+        self.watch_explicit(move |arg, content| {
+            let value = expr(arg, content);
+            target(content).set_if_neq(value, arg);
+        });
+    }
+
+    #[cfg_attr(do_cycle_debug, track_caller)]
+    fn bind_two_way_core<GA, GB, A, B, FB, FA>(
+        &mut self,
+        a: GA,
+        b: GB,
+        to_b: FB,
+        to_a: FA,
+    ) where
+        GA: 'static + Fn(&mut Content) -> &mut WatchedCore<'ctx, A, Owner>,
+        GB: 'static + Fn(&mut Content) -> &mut WatchedCore<'ctx, B, Owner>,
+        A: 'static + Clone + PartialEq,
+        B: 'static + Clone + PartialEq,
+        FB: 'static + Fn(&A) -> B,
+        FA: 'static + Fn(&B) -> A,
+    {
+        let last = Cell::new(None::<(A, B)>);
+        self.watch_explicit(move |arg, content| {
+            let current_a = a(content).get(arg).clone();
+            let current_b = b(content).get(arg).clone();
+            let (new_a, new_b) = match last.take() {
+                Some((old_a, _)) if old_a != current_a => {
+                    let new_b = to_b(&current_a);
+                    (current_a, new_b)
+                }
+                Some((_, old_b)) if old_b != current_b => {
+                    (to_a(&current_b), current_b)
+                }
+                Some(old) => {
+                    last.set(Some(old));
+                    return;
+                }
+                None => {
+                    let new_b = to_b(&current_a);
+                    (current_a, new_b)
+                }
+            };
+            a(content).set_if_neq(new_a.clone(), arg);
+            b(content).set_if_neq(new_b.clone(), arg);
+            // setting through `arg` does not re-run this watch, but it does
+            // drop it from the values' watchers, so read them again
+            a(content).get(arg);
+            b(content).get(arg);
+            last.set(Some((new_a, new_b)));
+        });
+    }
 }
@@ -0,0 +1,337 @@
+/* SPDX-License-Identifier: (Apache-2.0 OR MIT OR Zlib) */
+/* Copyright © 2026 Violet Leonard */
+
+use std::{
+    boxed::Box,
+    fmt,
+    panic::{catch_unwind, AssertUnwindSafe},
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        mpsc::{channel, Sender},
+        Arc, Mutex, MutexGuard, PoisonError,
+    },
+    thread,
+};
+
+use crate::{
+    DefaultOwner, ResourceJob, SyncWatchedGuard, SyncWatchedMeta, WatchArg,
+};
+
+fn lock<T: ?Sized>(mutex: &Mutex<T>) -> MutexGuard<'_, T> {
+    mutex.lock().unwrap_or_else(PoisonError::into_inner)
+}
+
+/// A fixed set of threads which run jobs in the order they were submitted.
+///
+/// The pool may be cloned to share it, and the threads exit once every
+/// clone has been dropped and the remaining jobs have finished.  A job which
+/// panics does not take its thread down with it; the panic is counted, and
+/// may be read with [`panicked_jobs`](Self::panicked_jobs).  It may
+/// also be used as the spawner of a
+/// [`WatchedResource`](crate::WatchedResource):
+///
+/// ```rust
+///# use drying_paint::*;
+/// let pool = WorkerPool::new(2);
+/// let resource: WatchedResource<u32, ()> =
+///     WatchedResource::with_spawner(move |job| pool.execute(job));
+/// ```
+#[derive(Clone)]
+pub struct WorkerPool {
+    sender: Sender<ResourceJob>,
+    panicked: Arc<AtomicUsize>,
+}
+
+impl fmt::Debug for WorkerPool {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "(WorkerPool)")
+    }
+}
+
+impl WorkerPool {
+    /// Start a pool with the given number of threads, at least one.
+    pub fn new(threads: usize) -> Self {
+        let (sender, receiver) = channel::<ResourceJob>();
+        let receiver = Arc::new(Mutex::new(receiver));
+        let panicked = Arc::new(AtomicUsize::new(0));
+        for _ in 0..threads.max(1) {
+            let receiver = Arc::clone(&receiver);
+            let panicked = Arc::clone(&panicked);
+            thread::spawn(move || loop {
+                let job = lock(&receiver).recv();
+                match job {
+                    Ok(job) => {
+                        // jobs share no state with the pool which a panic
+                        // could leave broken
+                        if catch_unwind(AssertUnwindSafe(job)).is_err() {
+                            panicked.fetch_add(1, Ordering::AcqRel);
+                        }
+                    }
+                    Err(_) => break,
+                }
+            });
+        }
+        Self { sender, panicked }
+    }
+
+    /// The number of jobs which have panicked so far.
+    pub fn panicked_jobs(&self) -> usize {
+        self.panicked.load(Ordering::Acquire)
+    }
+
+    /// Submit a job to be run on one of the threads of the pool.
+    pub fn execute<F>(&self, job: F)
+    where
+        F: 'static + Send + FnOnce(),
+    {
+        // the threads only exit once every sender is dropped
+        let _ = self.sender.send(Box::new(job));
+    }
+}
+
+struct Shared<T> {
+    version: AtomicUsize,
+    value: Mutex<T>,
+}
+
+/// A value which is derived from other values on the threads of a
+/// [`WorkerPool`].
+///
+/// Call [`compute`](Self::compute) from a watch function with a closure
+/// holding a snapshot of the inputs; it is run on the pool, and once it
+/// finishes its result replaces the value, and watch functions which read
+/// it are re-run on the next update of their context.  The previous value
+/// remains available until then.  If `compute` is called again before an
+/// earlier computation finishes, the result of the earlier one is
+/// discarded.  If the computation panics, the previous value remains, and
+/// the panic is counted by the pool.
+///
+/// # Examples
+/// ```rust
+///# use std::{rc::Rc, cell::RefCell};
+///# use drying_paint::*;
+/// struct Search {
+///     words: Watched<Vec<String>>,
+///     sorted: WatchedComputed<Vec<String>>,
+///     first: Option<String>,
+/// }
+///
+/// impl Watcher<'static> for Search {
+///     fn init(mut init: impl WatcherInit<'static, Self>) {
+///         init.watch(|root| {
+///             let mut words = root.words.clone();
+///             root.sorted.compute(move || {
+///                 words.sort();
+///                 words
+///             });
+///         });
+///         init.watch(|root| {
+///             root.first = root.sorted.get_auto().first().cloned();
+///         });
+///     }
+/// }
+///
+/// let search = Rc::new(RefCell::new(Search {
+///     words: Watched::new(vec!["paint".into(), "drying".into()]),
+///     sorted: WatchedComputed::new(Vec::new(), &WorkerPool::new(1)),
+///     first: None,
+/// }));
+/// let mut ctx = WatchContext::new();
+/// ctx.add_watcher(&Rc::downgrade(&search));
+/// ctx.run_until(|_| search.borrow().first.is_some());
+/// assert_eq!(search.borrow().first.as_deref(), Some("drying"));
+/// ```
+pub struct WatchedComputed<T> {
+    meta: SyncWatchedMeta,
+    shared: Arc<Shared<T>>,
+    pool: WorkerPool,
+}
+
+impl<T> fmt::Debug for WatchedComputed<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "(WatchedComputed)")
+    }
+}
+
+impl<T> WatchedComputed<T> {
+    /// Create a new computed value, holding `initial` until the first
+    /// computation finishes.
+    pub fn new(initial: T, pool: &WorkerPool) -> Self {
+        Self {
+            meta: SyncWatchedMeta::new(),
+            shared: Arc::new(Shared {
+                version: AtomicUsize::new(0),
+                value: Mutex::new(initial),
+            }),
+            pool: pool.clone(),
+        }
+    }
+
+    /// Get the latest computed value, binding a watch closure.
+    pub fn get<O: ?Sized>(
+        &self,
+        ctx: WatchArg<'_, '_, O>,
+    ) -> SyncWatchedGuard<'_, T> {
+        self.meta.watched(ctx);
+        self.get_unwatched()
+    }
+
+    /// Get the latest computed value, without binding any watch closure.
+    pub fn get_unwatched(&self) -> SyncWatchedGuard<'_, T> {
+        SyncWatchedGuard {
+            guard: lock(&self.shared.value),
+        }
+    }
+
+    pub fn get_auto(&self) -> SyncWatchedGuard<'_, T> {
+        WatchArg::<'_, 'static, DefaultOwner>::try_with_current(|arg| {
+            self.meta.watched(arg);
+        });
+        self.get_unwatched()
+    }
+}
+
+impl<T: 'static + Send> WatchedComputed<T> {
+    /// Start computing a new value, superseding any computation which is
+    /// still in progress.
+    pub fn compute<F>(&self, func: F)
+    where
+        F: 'static + Send + FnOnce() -> T,
+    {
+        // a job checks the version while holding the lock, so taking it
+        // here means no older result can be published after this point
+        let value = lock(&self.shared.value);
+        let version = self.shared.version.fetch_add(1, Ordering::AcqRel) + 1;
+        drop(value);
+        let shared = Arc::clone(&self.shared);
+        let trigger = self.meta.create_trigger();
+        self.pool.execute(move || {
+            if shared.version.load(Ordering::Acquire) != version {
+                // superseded before it even started
+                return;
+            }
+            let result = func();
+            let mut value = lock(&shared.value);
+            if shared.version.load(Ordering::Acquire) == version {
+                *value = result;
+                drop(value);
+                trigger.trigger();
+            }
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        cell::RefCell,
+        rc::Rc,
+        sync::{mpsc::channel, Arc, Barrier},
+    };
+
+    use crate::*;
+
+    struct Content {
+        input: Watched<u32>,
+        output: WatchedComputed<u32>,
+        seen: u32,
+    }
+
+    impl Watcher<'static> for Content {
+        fn init(mut init: impl WatcherInit<'static, Self>) {
+            init.watch(|root| {
+                root.seen = *root.output.get_auto();
+            });
+        }
+    }
+
+    struct Tenfold {
+        input: Watched<u32>,
+        output: WatchedComputed<u32>,
+        dispatched: u32,
+        seen: u32,
+    }
+
+    impl Watcher<'static> for Tenfold {
+        fn init(mut init: impl WatcherInit<'static, Self>) {
+            init.watch(|root| {
+                let input = *root.input;
+                root.dispatched += 1;
+                root.output.compute(move || {
+                    assert_ne!(input, 13, "unlucky input");
+                    input * 10
+                });
+            });
+            init.watch(|root| {
+                root.seen = *root.output.get_auto();
+            });
+        }
+    }
+
+    #[test]
+    fn input_change_recomputes() {
+        let pool = WorkerPool::new(1);
+        let content = Rc::new(RefCell::new(Tenfold {
+            input: Watched::new(1),
+            output: WatchedComputed::new(0, &pool),
+            dispatched: 0,
+            seen: 0,
+        }));
+        let mut ctx = WatchContext::new();
+        ctx.add_watcher(&Rc::downgrade(&content));
+        ctx.run_until(|_| content.borrow().seen == 10);
+        assert_eq!(content.borrow().dispatched, 1);
+        *content.borrow_mut().input = 13;
+        ctx.update();
+        assert_eq!(content.borrow().dispatched, 2);
+        // the panicking job doesn't take the only thread down with it
+        let (done, wait_done) = channel::<()>();
+        pool.execute(move || done.send(()).unwrap());
+        wait_done.recv().unwrap();
+        assert_eq!(pool.panicked_jobs(), 1);
+        ctx.update();
+        assert_eq!(content.borrow().seen, 10);
+        *content.borrow_mut().input = 4;
+        ctx.update();
+        assert_eq!(content.borrow().dispatched, 3);
+        ctx.run_until(|_| content.borrow().seen == 40);
+        assert_eq!(content.borrow().dispatched, 3);
+    }
+
+    #[test]
+    fn stale_result_dropped() {
+        let pool = WorkerPool::new(2);
+        let content = Rc::new(RefCell::new(Content {
+            input: Watched::new(0),
+            output: WatchedComputed::new(0, &pool),
+            seen: 0,
+        }));
+        let mut ctx = WatchContext::new();
+        ctx.add_watcher(&Rc::downgrade(&content));
+        let (release, gate) = channel::<()>();
+        let (started, wait_started) = channel::<()>();
+        content.borrow().output.compute(move || {
+            started.send(()).unwrap();
+            gate.recv().unwrap();
+            1
+        });
+        wait_started.recv().unwrap();
+        *content.borrow_mut().input = 2;
+        let input = *content.borrow().input;
+        content.borrow().output.compute(move || input * 10);
+        ctx.run_until(|_| content.borrow().seen != 0);
+        assert_eq!(content.borrow().seen, 20);
+        release.send(()).unwrap();
+        // once both threads are free, the first computation has finished
+        let barrier = Arc::new(Barrier::new(3));
+        for _ in 0..2 {
+            let barrier = Arc::clone(&barrier);
+            pool.execute(move || {
+                barrier.wait();
+            });
+        }
+        barrier.wait();
+        ctx.update();
+        assert_eq!(content.borrow().seen, 20);
+    }
+}
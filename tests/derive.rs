@@ -0,0 +1,132 @@
+/* SPDX-License-Identifier: (Apache-2.0 OR MIT OR Zlib) */
+/* Copyright © 2026 Violet Leonard */
+
+#![cfg(all(feature = "derive", feature = "std"))]
+
+use std::{cell::RefCell, rc::Rc};
+
+use drying_paint::{
+    watches, DefaultOwner, WatchArg, WatchContext, Watched, WatchedCore,
+    WatchedFields, Watcher,
+};
+
+#[derive(Default, Watcher)]
+struct Leaf {
+    source: Watched<u32>,
+    core: WatchedCore<'static, u32>,
+    auto: u32,
+    explicit: u32,
+}
+
+#[watches]
+impl Leaf {
+    #[watch]
+    fn copy_auto(&mut self) {
+        self.auto = *self.source;
+    }
+
+    #[watch]
+    fn copy_explicit(&mut self, arg: WatchArg<'_, 'static, DefaultOwner>) {
+        self.explicit = *self.core.get(arg);
+    }
+
+    #[allow(dead_code)]
+    fn not_watched(&mut self) {
+        unreachable!();
+    }
+}
+
+#[derive(Default, Watcher)]
+struct Tuple(#[watcher] Leaf, #[watcher] Leaf);
+
+#[derive(Default, Watcher)]
+struct Plain {
+    #[watcher]
+    leaf: Leaf,
+    #[watcher]
+    tuple: Tuple,
+}
+
+#[test]
+fn children_and_methods() {
+    let plain = Rc::new(RefCell::new(Plain::default()));
+    let mut ctx = WatchContext::new();
+    ctx.add_watcher(&Rc::downgrade(&plain));
+    *plain.borrow_mut().leaf.source = 3;
+    *plain.borrow_mut().tuple.1.source = 5;
+    *plain.borrow_mut().tuple.1.core.get_mut_external() = 6;
+    ctx.update();
+    let plain = plain.borrow();
+    assert_eq!((plain.leaf.auto, plain.leaf.explicit), (3, 0));
+    assert_eq!((plain.tuple.0.auto, plain.tuple.0.explicit), (0, 0));
+    assert_eq!((plain.tuple.1.auto, plain.tuple.1.explicit), (5, 6));
+}
+
+struct Owner;
+
+#[derive(Watcher)]
+#[watcher(ctx = 'a, owner = Owner)]
+struct Borrowed<'a> {
+    source: WatchedCore<'a, u32, Owner>,
+    dest: u32,
+}
+
+#[watches(ctx = 'a, owner = Owner)]
+impl<'a> Borrowed<'a> {
+    #[watch]
+    fn copy(&mut self, arg: WatchArg<'_, 'a, Owner>) {
+        self.dest = *self.source.get(arg);
+    }
+}
+
+#[test]
+fn explicit_context() {
+    let borrowed = Rc::new(RefCell::new(Borrowed {
+        source: WatchedCore::new(7),
+        dest: 0,
+    }));
+    let mut ctx = WatchContext::from_owner(Owner);
+    ctx.add_watcher(&Rc::downgrade(&borrowed));
+    assert_eq!(borrowed.borrow().dest, 7);
+    *borrowed.borrow_mut().source.get_mut_external() = 9;
+    ctx.update();
+    assert_eq!(borrowed.borrow().dest, 9);
+}
+
+#[derive(WatchedFields)]
+struct Pair<T> {
+    first: T,
+    second: T,
+}
+
+struct PairOwner {
+    pair: WatchedPair<'static, String, PairOwner>,
+    seen: String,
+    runs: u32,
+}
+
+#[test]
+fn fields_watched_separately() {
+    let mut ctx = WatchContext::from_owner(PairOwner {
+        pair: WatchedPair::new(Pair {
+            first: "drying".to_string(),
+            second: "paint".to_string(),
+        }),
+        seen: String::new(),
+        runs: 0,
+    });
+    ctx.add_watch(|owner, arg| {
+        owner.seen = owner.pair.get_first(arg).clone();
+        owner.runs += 1;
+    });
+    assert_eq!(ctx.owner().seen, "drying");
+    ctx.owner().pair.set_second_external("wet".to_string());
+    ctx.update();
+    assert_eq!(ctx.owner().runs, 1);
+    ctx.owner().pair.set_first_external("still".to_string());
+    ctx.update();
+    assert_eq!(ctx.owner().runs, 2);
+    assert_eq!(ctx.owner().seen, "still");
+    let pair = ctx.owner().pair.get_unwatched();
+    assert_eq!((&*pair.first, &*pair.second), ("still", "wet"));
+}
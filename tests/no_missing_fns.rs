@@ -32,6 +32,69 @@ fn test_no_missing_fns<'ctx>() {
     #[cfg(feature = "std")]
     function_exists(<WatchedCore<'static, f32>>::set_if_neq_auto);
 
+    function_exists(<WatchedCore<'ctx, u32>>::set_if_hash_neq);
+    function_exists(<WatchedCore<'ctx, u32>>::set_if_hash_neq_external);
+    #[cfg(feature = "std")]
+    function_exists(<WatchedCore<'static, u32>>::set_if_hash_neq_auto);
+
+    function_exists(
+        <WatchedCore<'ctx, u32>>::modify_if_hash_changed::<fn(&mut u32), ()>,
+    );
+    function_exists(
+        <WatchedCore<'ctx, u32>>::modify_if_hash_changed_external::<
+            fn(&mut u32),
+            (),
+        >,
+    );
+    #[cfg(feature = "std")]
+    function_exists(
+        <WatchedCore<'static, u32>>::modify_if_hash_changed_auto::<
+            fn(&mut u32),
+            (),
+        >,
+    );
+
+    function_exists(
+        <WatchedCore<'ctx, u32>>::modify_if_fingerprint_changed::<
+            fn(&u32) -> bool,
+            bool,
+            fn(&mut u32),
+            (),
+        >,
+    );
+    function_exists(
+        <WatchedCore<'ctx, u32>>::modify_if_fingerprint_changed_external::<
+            fn(&u32) -> bool,
+            bool,
+            fn(&mut u32),
+            (),
+        >,
+    );
+    #[cfg(feature = "std")]
+    function_exists(
+        <WatchedCore<'static, u32>>::modify_if_fingerprint_changed_auto::<
+            fn(&u32) -> bool,
+            bool,
+            fn(&mut u32),
+            (),
+        >,
+    );
+
+    function_exists(<WatchedCore<'ctx, f32>>::modify::<fn(&mut f32), ()>);
+    function_exists(
+        <WatchedCore<'ctx, f32>>::modify_external::<fn(&mut f32), ()>,
+    );
+    #[cfg(feature = "std")]
+    function_exists(
+        <WatchedCore<'static, f32>>::modify_auto::<fn(&mut f32), ()>,
+    );
+
+    function_exists(<WatchedCore<'ctx, f32>>::select::<fn(&f32) -> u8, u8>);
+    #[cfg(feature = "std")]
+    function_exists(
+        <WatchedCore<'static, f32>>::select_auto::<fn(&f32) -> u8, u8>,
+    );
+
     function_exists(<WatchedCellCore<'ctx, f32>>::get);
     function_exists(<WatchedCellCore<'ctx, f32>>::get_unwatched);
     #[cfg(feature = "std")]